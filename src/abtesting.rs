@@ -0,0 +1,306 @@
+//! [A/B testing API](https://www.algolia.com/doc/rest-api/abtesting/) client.
+//! ```no_run
+//! # use algoliasearch::{abtesting::{AbTest, AbTestVariant}, Client, Error};
+//! # use chrono::{Duration, Utc};
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<Error>> {
+//! let abtesting = Client::default().init_ab_testing(None);
+//! let res = abtesting
+//!     .add_ab_test(&AbTest {
+//!         name: "Custom ranking on sales".to_string(),
+//!         variants: vec![
+//!             AbTestVariant::new("products", 60),
+//!             AbTestVariant::new("products_sales", 40),
+//!         ],
+//!         end_at: Utc::now() + Duration::days(30),
+//!     })
+//!     .await?;
+//! let test = abtesting.get_ab_test(res.ab_test_id).await?;
+//! #   Ok(())
+//! # }
+//! ```
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    error::Error,
+    index::{params, SearchQuery},
+    request::{auth_headers, TransportExt},
+    transport::Transport,
+};
+
+enum_str!(AbTestStatus {
+    Active("active"),
+    Stopped("stopped"),
+    Expired("expired"),
+    Failed("failed"),
+});
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// A variant of a new A/B test
+pub struct AbTestVariant {
+    /// Index of the variant, usually a replica
+    pub index: String,
+    /// Percentage of the searches served by the variant
+    pub traffic_percentage: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Description
+    pub description: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "params::serialize_object"
+    )]
+    /// Search parameters applied to the searches of the variant
+    pub custom_search_parameters: Option<SearchQuery>,
+}
+
+impl AbTestVariant {
+    /// Variant searching `index` for a share of the traffic.
+    pub fn new(index: &str, traffic_percentage: u64) -> AbTestVariant {
+        AbTestVariant {
+            index: index.to_string(),
+            traffic_percentage,
+            description: None,
+            custom_search_parameters: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// A new A/B test
+pub struct AbTest {
+    /// Name
+    pub name: String,
+    /// Variants, the first one being the control
+    pub variants: Vec<AbTestVariant>,
+    /// End of the test
+    pub end_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// A variant of an A/B test, with its metrics
+pub struct AbTestVariantResult {
+    /// Index of the variant
+    pub index: String,
+    /// Percentage of the searches served by the variant
+    pub traffic_percentage: u64,
+    #[serde(default)]
+    /// Description
+    pub description: Option<String>,
+    #[serde(default)]
+    /// Search parameters applied to the searches of the variant
+    pub custom_search_parameters: Option<SearchQuery>,
+    #[serde(default)]
+    /// Number of searches
+    pub search_count: Option<u64>,
+    #[serde(default)]
+    /// Number of searches with click analytics enabled
+    pub tracked_search_count: Option<u64>,
+    #[serde(default)]
+    /// Number of users
+    pub user_count: Option<u64>,
+    #[serde(default)]
+    /// Number of searches without results
+    pub no_result_count: Option<u64>,
+    #[serde(default)]
+    /// Number of clicks
+    pub click_count: Option<u64>,
+    #[serde(default)]
+    /// Number of conversions
+    pub conversion_count: Option<u64>,
+    #[serde(default)]
+    /// Average position of the clicked hits
+    pub average_click_position: Option<f64>,
+    #[serde(default)]
+    /// Click-through rate
+    pub click_through_rate: Option<f64>,
+    #[serde(default)]
+    /// Conversion rate
+    pub conversion_rate: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// An A/B test, with its metrics
+pub struct AbTestResult {
+    #[serde(rename = "abTestID")]
+    /// A/B test ID
+    pub ab_test_id: u64,
+    /// Name
+    pub name: String,
+    /// Status
+    pub status: AbTestStatus,
+    /// Creation date
+    pub created_at: DateTime<Utc>,
+    /// End of the test
+    pub end_at: DateTime<Utc>,
+    #[serde(default)]
+    /// Confidence that the click-through rates of the variants differ, from 0 to 1
+    pub click_significance: Option<f64>,
+    #[serde(default)]
+    /// Confidence that the conversion rates of the variants differ, from 0 to 1
+    pub conversion_significance: Option<f64>,
+    /// Variants
+    pub variants: Vec<AbTestVariantResult>,
+}
+
+#[derive(Debug, Deserialize)]
+/// List A/B tests result
+pub struct ListAbTestsResult {
+    #[serde(default, deserialize_with = "deserialize_ab_tests")]
+    /// A/B tests of the page
+    pub abtests: Vec<AbTestResult>,
+    /// Number of A/B tests of the page
+    pub count: u64,
+    /// Number of A/B tests
+    pub total: u64,
+}
+
+// The API returns `null` rather than an empty list.
+fn deserialize_ab_tests<'de, D>(deserializer: D) -> Result<Vec<AbTestResult>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    serde::Deserialize::deserialize(deserializer).map(Option::unwrap_or_default)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Result of an operation on an A/B test
+pub struct AbTestTaskResult {
+    #[serde(rename = "abTestID")]
+    /// A/B test ID
+    pub ab_test_id: u64,
+    /// Index of the control variant
+    pub index: String,
+    #[serde(rename = "taskID")]
+    /// Task ID, see [Index::get_task_status](../index/struct.Index.html#method.get_task_status)
+    pub task_id: u64,
+}
+
+#[derive(Serialize)]
+struct ListAbTestsQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+/// A/B testing client
+pub struct AbTesting {
+    pub(crate) application_id: String,
+    pub(crate) api_key: Option<String>,
+    pub(crate) base_url: String,
+    pub(crate) transport: Arc<dyn Transport>,
+}
+
+impl AbTesting {
+    /// Create and start an A/B test.
+    pub async fn add_ab_test(&self, ab_test: &AbTest) -> Result<AbTestTaskResult, Error> {
+        let uri = format!("{}/abtests", self.base_url);
+        self.transport
+            .post(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .json(ab_test)
+            .send_json()
+            .await
+    }
+    /// Get an A/B test.
+    pub async fn get_ab_test(&self, ab_test_id: u64) -> Result<AbTestResult, Error> {
+        let uri = format!("{}/abtests/{}", self.base_url, ab_test_id);
+        self.transport
+            .get(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .send_json()
+            .await
+    }
+    /// List the A/B tests, 10 by default.
+    pub async fn list_ab_tests(
+        &self,
+        offset: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<ListAbTestsResult, Error> {
+        let uri = format!("{}/abtests", self.base_url);
+        self.transport
+            .get(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .query(&ListAbTestsQuery { offset, limit })
+            .send_json()
+            .await
+    }
+    /// Stop an A/B test. Its metrics are kept, and all searches go to the control index.
+    pub async fn stop_ab_test(&self, ab_test_id: u64) -> Result<AbTestTaskResult, Error> {
+        let uri = format!("{}/abtests/{}/stop", self.base_url, ab_test_id);
+        self.transport
+            .post(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .send_json()
+            .await
+    }
+    /// Delete an A/B test and its metrics.
+    pub async fn delete_ab_test(&self, ab_test_id: u64) -> Result<AbTestTaskResult, Error> {
+        let uri = format!("{}/abtests/{}", self.base_url, ab_test_id);
+        self.transport
+            .delete(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .send_json()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod abtesting_tests {
+    use super::*;
+    use crate::SearchQueryBuilder;
+    use chrono::TimeZone;
+    use serde_json;
+
+    #[test]
+    fn test_serialize() {
+        let mut variant = AbTestVariant::new("products_typo", 50);
+        variant.custom_search_parameters = Some(
+            SearchQueryBuilder::default()
+                .hits_per_page(5)
+                .build()
+                .unwrap(),
+        );
+        let ab_test = AbTest {
+            name: "typos".to_string(),
+            variants: vec![AbTestVariant::new("products", 50), variant],
+            end_at: Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap(),
+        };
+        assert_eq!(
+            serde_json::to_string(&ab_test).unwrap(),
+            r#"{"name":"typos","variants":[{"index":"products","trafficPercentage":50},{"index":"products_typo","trafficPercentage":50,"customSearchParameters":{"hitsPerPage":5}}],"endAt":"2024-02-01T00:00:00Z"}"#
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let res = serde_json::from_str::<ListAbTestsResult>(
+            r#"{"abtests":[{"abTestID":7,"name":"typos","status":"active","createdAt":"2024-01-01T00:00:00Z","endAt":"2024-02-01T00:00:00Z","clickSignificance":null,"variants":[{"index":"products","trafficPercentage":50,"searchCount":120,"clickThroughRate":0.25},{"index":"products_typo","trafficPercentage":50,"customSearchParameters":{"hitsPerPage":5}}]}],"count":1,"total":1}"#,
+        )
+        .unwrap();
+        let ab_test = &res.abtests[0];
+        assert_eq!(ab_test.status, AbTestStatus::Active);
+        assert_eq!(ab_test.variants[0].click_through_rate, Some(0.25));
+        assert_eq!(
+            ab_test.variants[1].custom_search_parameters,
+            Some(
+                SearchQueryBuilder::default()
+                    .hits_per_page(5)
+                    .build()
+                    .unwrap()
+            )
+        );
+        let res =
+            serde_json::from_str::<ListAbTestsResult>(r#"{"abtests":null,"count":0,"total":0}"#)
+                .unwrap();
+        assert!(res.abtests.is_empty());
+    }
+}
@@ -0,0 +1,419 @@
+//! [Analytics API](https://www.algolia.com/doc/rest-api/analytics/) client.
+//! ```no_run
+//! # use algoliasearch::{analytics::AnalyticsQueryBuilder, Client, Error};
+//! # use chrono::NaiveDate;
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<Error>> {
+//! let analytics = Client::default().init_analytics(None);
+//! let query = AnalyticsQueryBuilder::default()
+//!     .index("products")
+//!     .start_date(NaiveDate::from_ymd_opt(2024, 1, 1))
+//!     .end_date(NaiveDate::from_ymd_opt(2024, 1, 31))
+//!     .tags(vec!["mobile".to_string()])
+//!     .build()
+//!     .unwrap();
+//! let searches = analytics.get_top_searches(&query).await?;
+//! let rate = analytics.get_no_results_rate(&query).await?;
+//! #   Ok(())
+//! # }
+//! ```
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use serde::Serializer;
+
+use crate::{
+    error::Error,
+    request::{auth_headers, TransportExt},
+    transport::Transport,
+};
+
+#[derive(Clone, Builder, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// Parameters shared by the analytics endpoints.
+pub struct AnalyticsQuery {
+    #[builder(setter(into))]
+    /// Index name, required
+    index: String,
+    #[builder(default, setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// First day of the period, 8 days ago by default
+    start_date: Option<NaiveDate>,
+    #[builder(default, setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Last day of the period, yesterday by default
+    end_date: Option<NaiveDate>,
+    #[builder(default, setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Number of items to return, 10 by default
+    limit: Option<u64>,
+    #[builder(default, setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Position of the first item to return
+    offset: Option<u64>,
+    #[builder(default, setter(into))]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_tags"
+    )]
+    /// Only count searches made with all these
+    /// [analytics tags](https://www.algolia.com/doc/api-reference/api-parameters/analyticsTags/)
+    tags: Option<Vec<String>>,
+    #[builder(default, setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Include click and conversion statistics, for top searches and top hits
+    click_analytics: Option<bool>,
+}
+
+fn serialize_tags<S>(tags: &Option<Vec<String>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match tags {
+        Some(tags) => serializer.serialize_str(&tags.join(" AND ")),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[derive(Serialize)]
+struct SearchParameter<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    search: Option<&'a str>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// A search of the top searches
+pub struct TopSearch {
+    /// Query
+    pub search: String,
+    /// Number of searches
+    pub count: u64,
+    /// Number of hits the search returns
+    pub nb_hits: u64,
+    #[serde(default)]
+    /// Number of searches with click analytics enabled
+    pub tracked_search_count: Option<u64>,
+    #[serde(default)]
+    /// Number of clicks
+    pub click_count: Option<u64>,
+    #[serde(default)]
+    /// Click-through rate
+    pub click_through_rate: Option<f64>,
+    #[serde(default)]
+    /// Average position of the clicked hits
+    pub average_click_position: Option<f64>,
+    #[serde(default)]
+    /// Number of conversions
+    pub conversion_count: Option<u64>,
+    #[serde(default)]
+    /// Conversion rate
+    pub conversion_rate: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TopSearches {
+    searches: Vec<TopSearch>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// A search without results
+pub struct NoResultSearch {
+    /// Query
+    pub search: String,
+    /// Number of searches
+    pub count: u64,
+    /// Number of searches made with filters
+    pub with_filter_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct NoResultSearches {
+    searches: Vec<NoResultSearch>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// A hit of the top hits
+pub struct TopHit {
+    /// Object ID
+    pub hit: String,
+    /// Number of times the hit was returned
+    pub count: u64,
+    #[serde(default)]
+    /// Number of clicks
+    pub click_count: Option<u64>,
+    #[serde(default)]
+    /// Click-through rate
+    pub click_through_rate: Option<f64>,
+    #[serde(default)]
+    /// Number of conversions
+    pub conversion_count: Option<u64>,
+    #[serde(default)]
+    /// Conversion rate
+    pub conversion_rate: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TopHits {
+    hits: Vec<TopHit>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+/// A filter of the top filters
+pub struct TopFilter {
+    /// Attribute
+    pub attribute: String,
+    /// Operator, e.g. `:`
+    pub operator: String,
+    /// Value
+    pub value: String,
+    /// Number of searches using the filter
+    pub count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TopFilters {
+    values: Vec<TopFilter>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+/// Number of searches of a day
+pub struct DateCount {
+    /// Day
+    pub date: NaiveDate,
+    /// Number of searches
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+/// Number of searches over a period
+pub struct SearchCount {
+    /// Number of searches
+    pub count: u64,
+    /// Number of searches per day
+    pub dates: Vec<DateCount>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// Rate of searches without results of a day
+pub struct DateNoResultRate {
+    /// Day
+    pub date: NaiveDate,
+    /// Rate, `None` without searches
+    pub rate: Option<f64>,
+    /// Number of searches
+    pub count: u64,
+    /// Number of searches without results
+    pub no_result_count: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// Rate of searches without results over a period
+pub struct NoResultRate {
+    /// Rate, `None` without searches
+    pub rate: Option<f64>,
+    /// Number of searches
+    pub count: u64,
+    /// Number of searches without results
+    pub no_result_count: u64,
+    /// Rate per day
+    pub dates: Vec<DateNoResultRate>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// Click-through rate of a day
+pub struct DateClickThroughRate {
+    /// Day
+    pub date: NaiveDate,
+    /// Rate, `None` without tracked searches
+    pub rate: Option<f64>,
+    /// Number of clicks
+    pub click_count: u64,
+    /// Number of searches with click analytics enabled
+    pub tracked_search_count: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// Click-through rate over a period
+pub struct ClickThroughRate {
+    /// Rate, `None` without tracked searches
+    pub rate: Option<f64>,
+    /// Number of clicks
+    pub click_count: u64,
+    /// Number of searches with click analytics enabled
+    pub tracked_search_count: u64,
+    /// Rate per day
+    pub dates: Vec<DateClickThroughRate>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// Clicks on a range of positions
+pub struct ClickPosition {
+    /// First and last positions of the range, e.g. `[4, 7]`
+    pub position: [i64; 2],
+    /// Number of clicks
+    pub click_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClickPositions {
+    positions: Vec<ClickPosition>,
+}
+
+#[derive(Clone, Debug)]
+/// Analytics client
+pub struct Analytics {
+    pub(crate) application_id: String,
+    pub(crate) api_key: Option<String>,
+    pub(crate) base_url: String,
+    pub(crate) transport: Arc<dyn Transport>,
+}
+
+impl Analytics {
+    /// Most frequent searches.
+    pub async fn get_top_searches(&self, query: &AnalyticsQuery) -> Result<Vec<TopSearch>, Error> {
+        self.get::<TopSearches>("searches", query, None)
+            .await
+            .map(|res| res.searches)
+    }
+    /// Most frequent searches without results.
+    pub async fn get_searches_no_results(
+        &self,
+        query: &AnalyticsQuery,
+    ) -> Result<Vec<NoResultSearch>, Error> {
+        self.get::<NoResultSearches>("searches/noResults", query, None)
+            .await
+            .map(|res| res.searches)
+    }
+    /// Most frequent hits, for all searches or a given one.
+    pub async fn get_top_hits(
+        &self,
+        query: &AnalyticsQuery,
+        search: Option<&str>,
+    ) -> Result<Vec<TopHit>, Error> {
+        self.get::<TopHits>("hits", query, search)
+            .await
+            .map(|res| res.hits)
+    }
+    /// Most frequent filters, for all searches or a given one.
+    pub async fn get_top_filters(
+        &self,
+        query: &AnalyticsQuery,
+        search: Option<&str>,
+    ) -> Result<Vec<TopFilter>, Error> {
+        self.get::<TopFilters>("filters", query, search)
+            .await
+            .map(|res| res.values)
+    }
+    /// Number of searches.
+    pub async fn get_search_count(&self, query: &AnalyticsQuery) -> Result<SearchCount, Error> {
+        self.get("searches/count", query, None).await
+    }
+    /// Rate of searches without results.
+    pub async fn get_no_results_rate(&self, query: &AnalyticsQuery) -> Result<NoResultRate, Error> {
+        self.get("searches/noResultRate", query, None).await
+    }
+    /// Click-through rate.
+    pub async fn get_click_through_rate(
+        &self,
+        query: &AnalyticsQuery,
+    ) -> Result<ClickThroughRate, Error> {
+        self.get("clicks/clickThroughRate", query, None).await
+    }
+    /// Distribution of clicks by position.
+    pub async fn get_click_positions(
+        &self,
+        query: &AnalyticsQuery,
+    ) -> Result<Vec<ClickPosition>, Error> {
+        self.get::<ClickPositions>("clicks/positions", query, None)
+            .await
+            .map(|res| res.positions)
+    }
+    async fn get<R: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &AnalyticsQuery,
+        search: Option<&str>,
+    ) -> Result<R, Error> {
+        let uri = format!("{}/{}", self.base_url, path);
+        self.transport
+            .get(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .query(query)
+            .query(&SearchParameter { search })
+            .send_json()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod analytics_tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn test_serialize_query() {
+        let query = AnalyticsQueryBuilder::default()
+            .index("products")
+            .start_date(NaiveDate::from_ymd_opt(2024, 1, 1))
+            .end_date(NaiveDate::from_ymd_opt(2024, 1, 31))
+            .limit(5)
+            .tags(vec!["mobile".to_string(), "ios".to_string()])
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_urlencoded::to_string(&query).unwrap(),
+            "index=products&startDate=2024-01-01&endDate=2024-01-31&limit=5&tags=mobile+AND+ios"
+        );
+        assert!(AnalyticsQueryBuilder::default().limit(5).build().is_err());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        assert_eq!(
+            serde_json::from_str::<TopSearches>(
+                r#"{"searches":[{"search":"phone","count":12,"nbHits":140}]}"#
+            )
+            .unwrap()
+            .searches,
+            vec![TopSearch {
+                search: "phone".to_string(),
+                count: 12,
+                nb_hits: 140,
+                tracked_search_count: None,
+                click_count: None,
+                click_through_rate: None,
+                average_click_position: None,
+                conversion_count: None,
+                conversion_rate: None,
+            }]
+        );
+        assert_eq!(
+            serde_json::from_str::<NoResultRate>(
+                r#"{"rate":null,"count":0,"noResultCount":0,"dates":[{"date":"2024-01-01","rate":null,"count":0,"noResultCount":0}]}"#
+            )
+            .unwrap()
+            .dates[0]
+                .date,
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+        );
+        assert_eq!(
+            serde_json::from_str::<ClickPositions>(
+                r#"{"positions":[{"position":[1,1],"clickCount":10},{"position":[-1,-1],"clickCount":0}]}"#
+            )
+            .unwrap()
+            .positions[1],
+            ClickPosition {
+                position: [-1, -1],
+                click_count: 0
+            }
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! API keys.
+use std::collections::HashSet;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Deserializer, Serializer};
+use sha2::Sha256;
+
+use crate::{
+    error::Error,
+    filters::Filter,
+    index::{params, SearchQuery},
+};
+
+// [https://www.algolia.com/doc/guides/security/api-keys/#access-control-list-acl](https://www.algolia.com/doc/guides/security/api-keys/#access-control-list-acl)
+enum_str!(Acl {
+    Search("search"),
+    Browse("browse"),
+    AddObject("addObject"),
+    DeleteObject("deleteObject"),
+    ListIndexes("listIndexes"),
+    DeleteIndex("deleteIndex"),
+    Settings("settings"),
+    EditSettings("editSettings"),
+    Analytics("analytics"),
+    Recommendation("recommendation"),
+    Usage("usage"),
+    Logs("logs"),
+    SeeUnretrievableAttributes("seeUnretrievableAttributes"),
+});
+
+#[derive(Clone, Builder, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[builder(default)]
+#[serde(rename_all = "camelCase")]
+/// API key, see [https://www.algolia.com/doc/rest-api/search/#add-api-key](https://www.algolia.com/doc/rest-api/search/#add-api-key)
+pub struct ApiKey {
+    #[builder(setter(skip))]
+    #[serde(default, skip_serializing)]
+    /// The key itself, only set on keys returned by the API
+    pub value: Option<String>,
+    #[builder(setter(skip))]
+    #[serde(default, skip_serializing, with = "chrono::serde::ts_seconds_option")]
+    /// Creation date, only set on keys returned by the API
+    pub created_at: Option<DateTime<Utc>>,
+    #[builder(setter(into))]
+    /// Operations allowed with the key
+    pub acl: Vec<Acl>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Description
+    pub description: Option<String>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Indices the key can access, `*` wildcards are allowed
+    pub indexes: Option<Vec<String>>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Referers the key can be used from, `*` wildcards are allowed
+    pub referers: Option<Vec<String>>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Validity in seconds, `0` for a key that never expires
+    pub validity: Option<u64>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Maximum number of hits per query
+    pub max_hits_per_query: Option<u64>,
+    #[builder(setter(into))]
+    #[serde(
+        default,
+        rename = "maxQueriesPerIPPerHour",
+        skip_serializing_if = "Option::is_none"
+    )]
+    /// Maximum number of queries per IP and per hour
+    pub max_queries_per_ip_per_hour: Option<u64>,
+    #[builder(setter(into))]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_query_parameters",
+        deserialize_with = "deserialize_query_parameters"
+    )]
+    /// Search parameters forced on every query made with the key
+    pub query_parameters: Option<SearchQuery>,
+}
+
+impl ApiKey {
+    // Whether the settings of the key (not its value or creation date) are the same.
+    pub(crate) fn has_same_settings(&self, other: &ApiKey) -> bool {
+        self.acl.iter().collect::<HashSet<_>>() == other.acl.iter().collect::<HashSet<_>>()
+            && self.description.as_deref().unwrap_or_default()
+                == other.description.as_deref().unwrap_or_default()
+            && self.indexes.as_deref().unwrap_or_default()
+                == other.indexes.as_deref().unwrap_or_default()
+            && self.referers.as_deref().unwrap_or_default()
+                == other.referers.as_deref().unwrap_or_default()
+            && self.validity.unwrap_or(0) == other.validity.unwrap_or(0)
+            && self.max_hits_per_query.unwrap_or(0) == other.max_hits_per_query.unwrap_or(0)
+            && self.max_queries_per_ip_per_hour.unwrap_or(0)
+                == other.max_queries_per_ip_per_hour.unwrap_or(0)
+            && self.query_parameters.clone().unwrap_or_default()
+                == other.query_parameters.clone().unwrap_or_default()
+    }
+}
+
+fn serialize_query_parameters<S>(
+    query: &Option<SearchQuery>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match query {
+        Some(query) => serializer.serialize_str(&query.to_params()),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_query_parameters<'de, D>(deserializer: D) -> Result<Option<SearchQuery>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(params) if !params.is_empty() => SearchQuery::from_params(&params)
+            .map(Some)
+            .map_err(|_| serde::de::Error::custom(format!("invalid queryParameters: {}", params))),
+        _ => Ok(None),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Result of adding or restoring an API key
+pub struct AddApiKeyResult {
+    /// The key
+    pub key: String,
+    /// Creation date
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Result of updating an API key
+pub struct UpdateApiKeyResult {
+    /// The key
+    pub key: String,
+    /// Update date
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Result of deleting an API key
+pub struct DeleteApiKeyResult {
+    /// Deletion date
+    pub deleted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ListApiKeysResult {
+    pub(crate) keys: Vec<ApiKey>,
+}
+
+#[derive(Clone, Copy, Debug)]
+/// Operation to wait for with [Client::wait_for_api_key](../client/struct.Client.html#method.wait_for_api_key).
+pub enum ApiKeyOperation<'a> {
+    /// The key was added or restored
+    Add,
+    /// The key was updated with these settings
+    Update(&'a ApiKey),
+    /// The key was deleted
+    Delete,
+}
+
+#[derive(Clone, Builder, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[builder(default)]
+#[serde(rename_all = "camelCase")]
+/// Restrictions embedded in a [secured API key](https://www.algolia.com/doc/guides/security/api-keys/how-to/user-restricted-access-to-data/).
+pub struct SecuredKeyRestrictions {
+    #[builder(setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Filters applied to every search made with the key
+    pub filters: Option<Filter>,
+    #[builder(setter(into))]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "chrono::serde::ts_seconds_option"
+    )]
+    /// Expiration date of the key
+    pub valid_until: Option<DateTime<Utc>>,
+    #[builder(setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Indices the key can search
+    pub restrict_indices: Option<Vec<String>>,
+    #[builder(setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// IPv4 network allowed to use the key, e.g. `192.168.1.0/24`
+    pub restrict_sources: Option<String>,
+    #[builder(setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// User identifier used for rate-limiting
+    pub user_token: Option<String>,
+}
+
+impl SecuredKeyRestrictions {
+    /// Time left before the key expires, negative once expired,
+    /// `None` if it has no expiration date.
+    pub fn remaining_validity(&self) -> Option<Duration> {
+        self.valid_until.map(|valid_until| valid_until - Utc::now())
+    }
+}
+
+// Length of the hex encoded HMAC-SHA256 prefix of a secured key.
+const HMAC_HEX_LEN: usize = 64;
+
+pub(crate) fn generate_secured_api_key(
+    parent_api_key: &str,
+    restrictions: &SecuredKeyRestrictions,
+) -> String {
+    let params = params::to_params(restrictions);
+    let mut mac = Hmac::<Sha256>::new_from_slice(parent_api_key.as_bytes())
+        .expect("HMAC accepts keys of any size");
+    mac.update(params.as_bytes());
+    let hash = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    BASE64.encode(format!("{}{}", hash, params))
+}
+
+pub(crate) fn get_secured_api_key_restrictions(
+    secured_api_key: &str,
+) -> Result<SecuredKeyRestrictions, Error> {
+    let decoded = BASE64
+        .decode(secured_api_key)
+        .ok()
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .ok_or(Error::InvalidSecuredApiKey)?;
+    if decoded.len() < HMAC_HEX_LEN || !decoded.is_char_boundary(HMAC_HEX_LEN) {
+        return Err(Error::InvalidSecuredApiKey);
+    }
+    params::from_params(&decoded[HMAC_HEX_LEN..]).map_err(|_| Error::InvalidSecuredApiKey)
+}
+
+#[cfg(test)]
+mod api_key_tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn test_serialize() {
+        let key = ApiKeyBuilder::default()
+            .acl(vec![Acl::Search, Acl::Browse])
+            .indexes(vec!["products_*".to_string()])
+            .max_queries_per_ip_per_hour(100)
+            .query_parameters(
+                crate::SearchQueryBuilder::default()
+                    .hits_per_page(5)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_string(&key).unwrap(),
+            r#"{"acl":["search","browse"],"indexes":["products_*"],"maxQueriesPerIPPerHour":100,"queryParameters":"hitsPerPage=5"}"#
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let key = serde_json::from_str::<ApiKey>(
+            r#"{"value":"1eb37de6308abdccf9b760ddacb418b4","createdAt":1524154478,"acl":["search"],"validity":0,"description":"","queryParameters":""}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            key.value.as_deref(),
+            Some("1eb37de6308abdccf9b760ddacb418b4")
+        );
+        assert_eq!(key.created_at.unwrap().timestamp(), 1524154478);
+        assert_eq!(key.query_parameters, None);
+        assert!(key.has_same_settings(
+            &ApiKeyBuilder::default()
+                .acl(vec![Acl::Search])
+                .build()
+                .unwrap()
+        ));
+        assert!(!key.has_same_settings(
+            &ApiKeyBuilder::default()
+                .acl(vec![Acl::Browse])
+                .build()
+                .unwrap()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod secured_api_key_tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_generate() {
+        let restrictions = SecuredKeyRestrictionsBuilder::default()
+            .filters(crate::filters::parse("_tags:user_42").unwrap())
+            .valid_until(Utc.timestamp_opt(1_700_000_000, 0).unwrap())
+            .restrict_indices(vec!["products".to_string()])
+            .user_token("user_42".to_string())
+            .build()
+            .unwrap();
+        assert_eq!(
+            generate_secured_api_key("parent", &restrictions),
+            "ZDBiNjlmMTI0ZDFlNGUwYWI4ZmY0YmI4MTA5ZWU0N2VjNTFhYzk4MDU2MjJiMDI1ZTZlMWM0YjM4\
+             ZDU0OGMzMWZpbHRlcnM9X3RhZ3MlM0F1c2VyXzQyJnJlc3RyaWN0SW5kaWNlcz0lNUIlMjJwcm9k\
+             dWN0cyUyMiU1RCZ1c2VyVG9rZW49dXNlcl80MiZ2YWxpZFVudGlsPTE3MDAwMDAwMDA="
+        );
+    }
+
+    #[test]
+    fn test_restrictions() {
+        let restrictions = SecuredKeyRestrictionsBuilder::default()
+            .filters(crate::filters::parse("visible_by:group_1 OR visible_by:public").unwrap())
+            .valid_until(Utc.timestamp_opt(1_700_000_000, 0).unwrap())
+            .restrict_sources("192.168.1.0/24".to_string())
+            .build()
+            .unwrap();
+        let key = generate_secured_api_key("parent", &restrictions);
+        assert_eq!(
+            get_secured_api_key_restrictions(&key).unwrap(),
+            restrictions
+        );
+        assert!(restrictions.remaining_validity().unwrap() < Duration::zero());
+        assert_eq!(SecuredKeyRestrictions::default().remaining_validity(), None);
+    }
+
+    #[test]
+    fn test_invalid() {
+        for key in &["not base64!", "c2hvcnQ=", ""] {
+            match get_secured_api_key_restrictions(key) {
+                Err(Error::InvalidSecuredApiKey) => {}
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+    }
+}
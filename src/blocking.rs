@@ -0,0 +1,378 @@
+//! Blocking versions of the [Client](../client/struct.Client.html) and of the
+//! [Index](../index/struct.Index.html), for programs without an async runtime.
+//! They run the async API on an internal runtime, so they must not be used
+//! from within an async context, and the last clone of a client or of an index
+//! must not be dropped there either: dropping the runtime in an async context panics.
+//! ```no_run
+//! # #[macro_use] extern crate serde_derive;
+//! # use algoliasearch::{blocking::Client, settings::IndexSettingsBuilder, Error};
+//! #[derive(Debug, Deserialize, Serialize)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! # fn main() -> Result<(), Box<Error>> {
+//! let index = Client::default().init_index::<User>("users");
+//! let settings = IndexSettingsBuilder::default()
+//!     .searchable_attributes(vec!["name".to_string()])
+//!     .build()
+//!     .unwrap();
+//! index.set_settings(settings, None)?;
+//! #   Ok(())
+//! # }
+//! ```
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::runtime::{Builder, Runtime};
+
+use crate::{
+    api_key::{AddApiKeyResult, ApiKey, ApiKeyOperation, DeleteApiKeyResult, UpdateApiKeyResult},
+    clusters::{
+        AssignUserIdResult, Cluster, ListUserIdsResult, PendingMappingsResult, RemoveUserIdResult,
+        SearchUserIdsQuery, SearchUserIdsResult, UserId,
+    },
+    credentials::CredentialsProvider,
+    dictionaries::{
+        Dictionary, DictionaryEntry, DictionarySearchQuery, DictionarySearchResult,
+        DictionarySettings, DictionaryTaskResult,
+    },
+    error::Error,
+    index::{
+        self, cache::SearchCache, settings, task, AddObjectResult, BatchedOperatioResult,
+        DeleteObjectResult, SearchQuery, SearchResult, UpdateOperationResult,
+    },
+    logs::{LogEntry, LogType},
+    middleware::Interceptor,
+    rate_limit::RateLimit,
+    recommend::{RecommendRequest, RecommendResult},
+    request::RequestOptions,
+    transport::Transport,
+};
+
+// Methods blocking on the async method of the same name of `self.inner`.
+macro_rules! blocking_methods {
+    ($link:expr; $(
+        fn $name:ident $(<$param:ident: $bound:path>)? (&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;
+    )*) => {
+        $(
+            #[doc = concat!(
+                "Blocking version of [", stringify!($name), "](",
+                $link, "#method.", stringify!($name), ")."
+            )]
+            pub fn $name $(<$param: $bound>)? (&self $(, $arg: $ty)*) -> $ret {
+                self.runtime.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+/// Blocking Algolia client
+#[derive(Clone, Debug)]
+pub struct Client {
+    inner: crate::Client,
+    runtime: Arc<Runtime>,
+}
+
+impl Client {
+    /// Initialize the client, see [Client::new](../client/struct.Client.html#method.new).
+    pub fn new(application_id: &str, api_key: &str) -> Client {
+        Client::from_async(crate::Client::new(application_id, api_key))
+    }
+    /// Blocking client sending the requests of an async client.
+    pub fn from_async(client: crate::Client) -> Client {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to build the runtime of the blocking client");
+        Client {
+            inner: client,
+            runtime: Arc::new(runtime),
+        }
+    }
+    /// Set your client's APPLICATION_ID.
+    pub fn application_id(mut self, application_id: &str) -> Client {
+        self.inner = self.inner.application_id(application_id);
+        self
+    }
+    /// Set your client's API_KEY.
+    pub fn api_key(mut self, api_key: &str) -> Client {
+        self.inner = self.inner.api_key(api_key);
+        self
+    }
+    /// See [Client::transport](../client/struct.Client.html#method.transport).
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Client {
+        self.inner = self.inner.transport(transport);
+        self
+    }
+    /// See [Client::interceptor](../client/struct.Client.html#method.interceptor).
+    pub fn interceptor(mut self, interceptor: impl Interceptor + 'static) -> Client {
+        self.inner = self.inner.interceptor(interceptor);
+        self
+    }
+    /// See [Client::credentials_provider](../client/struct.Client.html#method.credentials_provider).
+    pub fn credentials_provider(mut self, provider: impl CredentialsProvider + 'static) -> Client {
+        self.inner = self.inner.credentials_provider(provider);
+        self
+    }
+    /// See [Client::rate_limit](../client/struct.Client.html#method.rate_limit).
+    pub fn rate_limit(mut self, limit: RateLimit) -> Client {
+        self.inner = self.inner.rate_limit(limit);
+        self
+    }
+    /// See [Client::gzip](../client/struct.Client.html#method.gzip).
+    #[cfg(feature = "gzip")]
+    pub fn gzip(mut self, min_body_size: usize) -> Client {
+        self.inner = self.inner.gzip(min_body_size);
+        self
+    }
+    /// The async client, e.g. to initialize the analytics client, whose calls are
+    /// then made with [block_on](#method.block_on).
+    pub fn as_async(&self) -> &crate::Client {
+        &self.inner
+    }
+    /// Run a future of the async API on the runtime of the client.
+    /// ```no_run
+    /// # use algoliasearch::{analytics::AnalyticsQueryBuilder, blocking::Client, Error};
+    /// # fn main() -> Result<(), Box<Error>> {
+    /// let client = Client::default();
+    /// let analytics = client.as_async().init_analytics(None);
+    /// let query = AnalyticsQueryBuilder::default()
+    ///     .index("products")
+    ///     .build()
+    ///     .unwrap();
+    /// let searches = client.block_on(analytics.get_top_searches(&query))?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+    /// Initialize the client index.
+    pub fn init_index<T>(&self, index_name: &str) -> Index<T> {
+        Index {
+            inner: self.inner.init_index(index_name),
+            runtime: self.runtime.clone(),
+        }
+    }
+    blocking_methods! {
+        "../client/struct.Client.html";
+        fn add_api_key(&self, key: &ApiKey) -> Result<AddApiKeyResult, Error>;
+        fn update_api_key(&self, key: &str, settings: &ApiKey) -> Result<UpdateApiKeyResult, Error>;
+        fn delete_api_key(&self, key: &str) -> Result<DeleteApiKeyResult, Error>;
+        fn restore_api_key(&self, key: &str) -> Result<AddApiKeyResult, Error>;
+        fn get_api_key(&self, key: &str) -> Result<ApiKey, Error>;
+        fn list_api_keys(&self) -> Result<Vec<ApiKey>, Error>;
+        fn wait_for_api_key(&self, key: &str, operation: ApiKeyOperation<'_>) -> Result<(), Error>;
+        fn get_recommendations<T: DeserializeOwned>(
+            &self,
+            requests: Vec<RecommendRequest>
+        ) -> Result<Vec<RecommendResult<T>>, Error>;
+        fn save_dictionary_entries<E: DictionaryEntry>(
+            &self,
+            entries: &[E]
+        ) -> Result<DictionaryTaskResult, Error>;
+        fn replace_dictionary_entries<E: DictionaryEntry>(
+            &self,
+            entries: &[E]
+        ) -> Result<DictionaryTaskResult, Error>;
+        fn delete_dictionary_entries(
+            &self,
+            dictionary: Dictionary,
+            object_ids: &[&str]
+        ) -> Result<DictionaryTaskResult, Error>;
+        fn clear_dictionary_entries(
+            &self,
+            dictionary: Dictionary
+        ) -> Result<DictionaryTaskResult, Error>;
+        fn search_dictionary_entries<E: DictionaryEntry>(
+            &self,
+            query: &DictionarySearchQuery
+        ) -> Result<DictionarySearchResult<E>, Error>;
+        fn get_dictionary_settings(&self) -> Result<DictionarySettings, Error>;
+        fn set_dictionary_settings(
+            &self,
+            settings: &DictionarySettings
+        ) -> Result<DictionaryTaskResult, Error>;
+        fn get_logs(
+            &self,
+            offset: Option<u64>,
+            length: Option<u64>,
+            log_type: Option<LogType>,
+            index_name: Option<&str>
+        ) -> Result<Vec<LogEntry>, Error>;
+        fn list_clusters(&self) -> Result<Vec<Cluster>, Error>;
+        fn assign_user_id(&self, user_id: &str, cluster: &str) -> Result<AssignUserIdResult, Error>;
+        fn batch_assign_user_ids(
+            &self,
+            user_ids: &[&str],
+            cluster: &str
+        ) -> Result<AssignUserIdResult, Error>;
+        fn get_user_id(&self, user_id: &str) -> Result<UserId, Error>;
+        fn get_top_user_ids(&self) -> Result<HashMap<String, Vec<UserId>>, Error>;
+        fn list_user_ids(
+            &self,
+            page: Option<u64>,
+            hits_per_page: Option<u64>
+        ) -> Result<ListUserIdsResult, Error>;
+        fn search_user_ids(&self, query: &SearchUserIdsQuery) -> Result<SearchUserIdsResult, Error>;
+        fn remove_user_id(&self, user_id: &str) -> Result<RemoveUserIdResult, Error>;
+        fn has_pending_mappings(&self, get_clusters: bool) -> Result<PendingMappingsResult, Error>;
+    }
+}
+
+impl Default for Client {
+    fn default() -> Client {
+        Client::from_async(crate::Client::default())
+    }
+}
+
+#[derive(Debug)]
+/// Blocking index
+pub struct Index<T> {
+    inner: index::Index<T>,
+    runtime: Arc<Runtime>,
+}
+
+impl<T> Index<T> {
+    /// Index name
+    pub fn index_name(&self) -> &str {
+        &self.inner.index_name
+    }
+    /// See [Index::with_user_id](../index/struct.Index.html#method.with_user_id).
+    pub fn with_user_id(&self, user_id: &str) -> Result<Index<T>, Error> {
+        Ok(Index {
+            inner: self.inner.with_user_id(user_id)?,
+            runtime: self.runtime.clone(),
+        })
+    }
+    /// See [Index::with_cache](../index/struct.Index.html#method.with_cache).
+    pub fn with_cache(&self, cache: &SearchCache) -> Index<T> {
+        Index {
+            inner: self.inner.with_cache(cache),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Serialize> Index<T> {
+    blocking_methods! {
+        "../index/struct.Index.html";
+        fn search(&self, query: impl Into<SearchQuery>) -> Result<SearchResult<T>, Error>;
+        fn search_with_options(
+            &self,
+            query: impl Into<SearchQuery>,
+            options: &RequestOptions
+        ) -> Result<SearchResult<T>, Error>;
+        fn get_object(
+            &self,
+            object_id: &str,
+            attributes_to_retrieve: Option<&[&str]>
+        ) -> Result<T, Error>;
+        fn get_object_with_options(
+            &self,
+            object_id: &str,
+            attributes_to_retrieve: Option<&[&str]>,
+            options: &RequestOptions
+        ) -> Result<T, Error>;
+        fn add_object(&self, object: &T) -> Result<AddObjectResult, Error>;
+        fn add_object_with_options(
+            &self,
+            object: &T,
+            options: &RequestOptions
+        ) -> Result<AddObjectResult, Error>;
+        fn add_objects(&self, objects: &[&T]) -> Result<BatchedOperatioResult, Error>;
+        fn add_objects_with_options(
+            &self,
+            objects: &[&T],
+            options: &RequestOptions
+        ) -> Result<BatchedOperatioResult, Error>;
+        fn update_object(
+            &self,
+            object: &T,
+            object_id: &str
+        ) -> Result<UpdateOperationResult, Error>;
+        fn update_object_with_options(
+            &self,
+            object: &T,
+            object_id: &str,
+            options: &RequestOptions
+        ) -> Result<UpdateOperationResult, Error>;
+        fn update_objects(&self, objects: &[&T]) -> Result<BatchedOperatioResult, Error>;
+        fn update_objects_with_options(
+            &self,
+            objects: &[&T],
+            options: &RequestOptions
+        ) -> Result<BatchedOperatioResult, Error>;
+        fn delete_object(&self, object_id: &str) -> Result<DeleteObjectResult, Error>;
+        fn delete_object_with_options(
+            &self,
+            object_id: &str,
+            options: &RequestOptions
+        ) -> Result<DeleteObjectResult, Error>;
+        fn clear_objects(&self) -> Result<UpdateOperationResult, Error>;
+        fn clear_objects_with_options(
+            &self,
+            options: &RequestOptions
+        ) -> Result<UpdateOperationResult, Error>;
+        fn get_settings(&self) -> Result<settings::IndexSettings, Error>;
+        fn get_settings_with_options(
+            &self,
+            options: &RequestOptions
+        ) -> Result<settings::IndexSettings, Error>;
+        fn set_settings(
+            &self,
+            settings: settings::IndexSettings,
+            forward_to_replicas: Option<bool>
+        ) -> Result<UpdateOperationResult, Error>;
+        fn set_settings_with_options(
+            &self,
+            settings: settings::IndexSettings,
+            options: &RequestOptions
+        ) -> Result<UpdateOperationResult, Error>;
+        fn get_task_status(&self, task_id: u64) -> Result<task::TaskStatus, Error>;
+        fn get_task_status_with_options(
+            &self,
+            task_id: u64,
+            options: &RequestOptions
+        ) -> Result<task::TaskStatus, Error>;
+    }
+}
+
+#[cfg(test)]
+mod blocking_tests {
+    use super::*;
+    use futures::future::BoxFuture;
+
+    use crate::transport::{HttpRequest, HttpResponse};
+
+    #[derive(Debug)]
+    struct Hits;
+
+    impl Transport for Hits {
+        fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+            Box::pin(async move {
+                assert_eq!(request.path(), "/1/indexes/users/query");
+                Ok(HttpResponse {
+                    status: 200,
+                    headers: Default::default(),
+                    body: br#"{"hits":[{"name":"Bernardo"}],"page":0,"nbHits":1,"nbPages":1,"hitsPerPage":20,"processingTimeMS":1,"query":"bern","params":"query=bern","exhaustiveNbHits":true}"#.to_vec(),
+                })
+            })
+        }
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct User {
+        name: String,
+    }
+
+    #[test]
+    fn test_search() {
+        let index = Client::new("APP_ID", "API_KEY")
+            .transport(Hits)
+            .init_index::<User>("users");
+        let res = index.search("bern").unwrap();
+        assert_eq!(res.hits[0].name, "Bernardo");
+    }
+}
@@ -1,15 +1,72 @@
-use std::{env, marker::PhantomData};
+use std::{collections::HashMap, env, marker::PhantomData, sync::Arc, time::Duration};
 
-use crate::index;
+use futures::{stream, Stream, TryStreamExt};
+use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    abtesting, analytics,
+    api_key::{
+        self, AddApiKeyResult, ApiKey, ApiKeyOperation, DeleteApiKeyResult, ListApiKeysResult,
+        SecuredKeyRestrictions, UpdateApiKeyResult,
+    },
+    clusters::{
+        AssignUserIdBody, AssignUserIdResult, BatchAssignUserIdsBody, Cluster, ListClustersResult,
+        ListUserIdsQuery, ListUserIdsResult, PendingMappingsQuery, PendingMappingsResult,
+        RemoveUserIdResult, SearchUserIdsQuery, SearchUserIdsResult, TopUserIdsResult, UserId,
+    },
+    credentials::{CredentialsProvider, CredentialsTransport},
+    dictionaries::{
+        Dictionary, DictionaryBatch, DictionaryEntry, DictionaryOperation, DictionarySearchQuery,
+        DictionarySearchResult, DictionarySettings, DictionaryTaskResult,
+    },
+    error::Error,
+    index, insights,
+    logs::{LogEntry, LogType, LogsQuery, LogsResult},
+    middleware::{InterceptedTransport, Interceptor},
+    personalization, query_suggestions,
+    rate_limit::{RateLimit, RateLimitedTransport, RateLimiter},
+    recommend::{RecommendRequest, RecommendRequests, RecommendResult, RecommendResults},
+    request::{auth_headers, encode_path_segment, TransportExt},
+    runtime,
+    transport::{ReqwestTransport, Transport},
+};
 
 const ALGOLIA_APPLICATION_ID_VARIABLE: &str = "ALGOLIA_APPLICATION_ID";
 const ALGOLIA_API_KEY_VARIABLE: &str = "ALGOLIA_API_KEY";
+// about a minute of polling, at most 1s apart
+const WAIT_FOR_API_KEY_MAX_ATTEMPTS: u64 = 60;
+
+// Region of the analytics, insights, personalization and query suggestions data.
+enum_str!(Region {
+    Us("us"),
+    De("de"),
+});
+
+impl Region {
+    // Name of the region in the hosts of the personalization and query suggestions
+    // APIs, which call the European region `eu`.
+    fn eu_name(&self) -> &'static str {
+        match self {
+            Region::Us => "us",
+            Region::De => "eu",
+        }
+    }
+}
 
 /// Algolia client
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Client {
     application_id: Option<String>,
     api_key: Option<String>,
+    // transport of the requests, wrapped with the interceptors
+    transport: Arc<dyn Transport>,
+    base_transport: Arc<dyn Transport>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    #[cfg(feature = "gzip")]
+    gzip_min_body_size: Option<usize>,
+    credentials_provider: Option<Arc<dyn CredentialsProvider>>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
 }
 
 impl Client {
@@ -19,6 +76,7 @@ impl Client {
         Client {
             application_id: Some(application_id.to_owned()),
             api_key: Some(api_key.to_owned()),
+            ..Client::with_default_transport()
         }
     }
     /// Set your client's [APPLICATION_ID](https://www.algolia.com/doc/guides/sending-and-managing-data/send-and-update-your-data/how-to/importing-with-the-api/#application-id).
@@ -31,6 +89,38 @@ impl Client {
         self.api_key = Some(api_key.to_owned());
         self
     }
+    /// Send the requests of the client and of everything it initializes through a
+    /// [Transport](../transport/trait.Transport.html).
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Client {
+        self.base_transport = Arc::new(transport);
+        self.chain_interceptors()
+    }
+    /// Run an [Interceptor](../middleware/trait.Interceptor.html) around the requests of the
+    /// client and of everything it initializes afterwards.
+    pub fn interceptor(mut self, interceptor: impl Interceptor + 'static) -> Client {
+        self.interceptors.push(Arc::new(interceptor));
+        self.chain_interceptors()
+    }
+    /// Get the API key from a [CredentialsProvider](../credentials/trait.CredentialsProvider.html)
+    /// on each request of the client and of everything it initializes afterwards, instead of
+    /// the [API_KEY](#method.api_key), which is then optional.
+    pub fn credentials_provider(mut self, provider: impl CredentialsProvider + 'static) -> Client {
+        self.credentials_provider = Some(Arc::new(provider));
+        self.chain_interceptors()
+    }
+    /// Limit the rate and the concurrency of the requests of the client, of its clones
+    /// and of everything it initializes afterwards, see [RateLimit](../rate_limit/struct.RateLimit.html).
+    pub fn rate_limit(mut self, limit: RateLimit) -> Client {
+        self.rate_limiter = Some(Arc::new(RateLimiter::new(&limit)));
+        self.chain_interceptors()
+    }
+    /// Compress with gzip the bodies of writes of at least `min_body_size` bytes, such as
+    /// large batches. Responses are accepted compressed whatever the size.
+    #[cfg(feature = "gzip")]
+    pub fn gzip(mut self, min_body_size: usize) -> Client {
+        self.gzip_min_body_size = Some(min_body_size);
+        self.chain_interceptors()
+    }
     /// Initialize the client index, providing your [INDEX_NAME](#).
     /// ```no_run
     /// # #[macro_use] extern crate serde_derive;
@@ -41,20 +131,703 @@ impl Client {
     /// let index = Client::default().init_index::<User>("users");
     /// # }
     /// ```
-    pub fn init_index<T>(self, index_name: &str) -> index::Index<T> {
-        if self.application_id.is_none() || self.api_key.is_none() {
-            panic!("application_id and/or api_key are not initialized");
-        }
+    pub fn init_index<T>(&self, index_name: &str) -> index::Index<T> {
+        let (application_id, api_key) = self.credentials();
         index::Index {
-            application_id: self.application_id.clone().expect("can't panic"),
-            api_key: self.api_key.expect("can't panic"),
+            application_id: application_id.to_owned(),
+            api_key: api_key.map(str::to_owned),
             index_name: index_name.to_owned(),
+            base_url: format!("https://{}-dsn.algolia.net/1", application_id),
+            transport: self.transport.clone(),
+            user_id: None,
+            cache: None,
+            index_type: PhantomData,
+        }
+    }
+    /// Initialize the [Insights](../insights/struct.Insights.html) client,
+    /// for the given region or the closest one when `None`.
+    pub fn init_insights(&self, region: Option<Region>) -> insights::Insights {
+        let (application_id, api_key) = self.credentials();
+        let host = match region {
+            Some(Region::Us) => "insights.us.algolia.io",
+            Some(Region::De) => "insights.de.algolia.io",
+            None => "insights.algolia.io",
+        };
+        insights::Insights {
+            application_id: application_id.to_owned(),
+            api_key: api_key.map(str::to_owned),
+            base_url: format!("https://{}/1", host),
+            transport: self.transport.clone(),
+        }
+    }
+    /// Initialize the [Analytics](../analytics/struct.Analytics.html) client,
+    /// for the region of the application, the United States when `None`.
+    pub fn init_analytics(&self, region: Option<Region>) -> analytics::Analytics {
+        let (application_id, api_key) = self.credentials();
+        let host = match region {
+            Some(Region::Us) => "analytics.us.algolia.com",
+            Some(Region::De) => "analytics.de.algolia.com",
+            None => "analytics.algolia.com",
+        };
+        analytics::Analytics {
+            application_id: application_id.to_owned(),
+            api_key: api_key.map(str::to_owned),
+            base_url: format!("https://{}/2", host),
+            transport: self.transport.clone(),
+        }
+    }
+    /// Initialize the [A/B testing](../abtesting/struct.AbTesting.html) client,
+    /// for the region of the application's analytics, the United States when `None`.
+    pub fn init_ab_testing(&self, region: Option<Region>) -> abtesting::AbTesting {
+        let analytics = self.init_analytics(region);
+        abtesting::AbTesting {
+            application_id: analytics.application_id,
+            api_key: analytics.api_key,
+            base_url: analytics.base_url,
+            transport: analytics.transport,
+        }
+    }
+    /// Initialize the [Personalization](../personalization/struct.Personalization.html) client,
+    /// for the region where the personalization data of the application is stored,
+    /// [Region::De](enum.Region.html#variant.De) for the European one.
+    pub fn init_personalization(&self, region: Region) -> personalization::Personalization {
+        let (application_id, api_key) = self.credentials();
+        personalization::Personalization {
+            application_id: application_id.to_owned(),
+            api_key: api_key.map(str::to_owned),
+            base_url: format!("https://personalization.{}.algolia.com/1", region.eu_name()),
+            transport: self.transport.clone(),
+        }
+    }
+    /// Initialize the [Query Suggestions](../query_suggestions/struct.QuerySuggestions.html) client,
+    /// for the region where the suggestions of the application are built,
+    /// [Region::De](enum.Region.html#variant.De) for the European one.
+    pub fn init_query_suggestions(&self, region: Region) -> query_suggestions::QuerySuggestions {
+        let (application_id, api_key) = self.credentials();
+        query_suggestions::QuerySuggestions {
+            application_id: application_id.to_owned(),
+            api_key: api_key.map(str::to_owned),
             base_url: format!(
-                "https://{}-dsn.algolia.net/1",
-                self.application_id.expect("can't panic")
+                "https://query-suggestions.{}.algolia.com/1",
+                region.eu_name()
             ),
-            index_type: PhantomData,
+            transport: self.transport.clone(),
+        }
+    }
+    /// Add an API key.
+    /// ```no_run
+    /// # use algoliasearch::{api_key::{Acl, ApiKeyBuilder, ApiKeyOperation}, Client, Error};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<Error>> {
+    /// let client = Client::default();
+    /// let key = ApiKeyBuilder::default()
+    ///     .acl(vec![Acl::Search])
+    ///     .indexes(vec!["products_*".to_string()])
+    ///     .build()
+    ///     .unwrap();
+    /// let res = client.add_api_key(&key).await?;
+    /// client.wait_for_api_key(&res.key, ApiKeyOperation::Add).await?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn add_api_key(&self, key: &ApiKey) -> Result<AddApiKeyResult, Error> {
+        let uri = format!("{}/keys", self.base_url());
+        self.transport
+            .post(&uri)
+            .headers(self.get_headers())
+            .json(key)
+            .send_json()
+            .await
+    }
+    /// Replace the settings of an API key.
+    pub async fn update_api_key(
+        &self,
+        key: &str,
+        settings: &ApiKey,
+    ) -> Result<UpdateApiKeyResult, Error> {
+        let uri = format!("{}/keys/{}", self.base_url(), key);
+        self.transport
+            .put(&uri)
+            .headers(self.get_headers())
+            .json(settings)
+            .send_json()
+            .await
+    }
+    /// Delete an API key.
+    pub async fn delete_api_key(&self, key: &str) -> Result<DeleteApiKeyResult, Error> {
+        let uri = format!("{}/keys/{}", self.base_url(), key);
+        self.transport
+            .delete(&uri)
+            .headers(self.get_headers())
+            .send_json()
+            .await
+    }
+    /// Restore a deleted API key.
+    pub async fn restore_api_key(&self, key: &str) -> Result<AddApiKeyResult, Error> {
+        let uri = format!("{}/keys/{}/restore", self.base_url(), key);
+        self.transport
+            .post(&uri)
+            .headers(self.get_headers())
+            .send_json()
+            .await
+    }
+    /// Get an API key.
+    pub async fn get_api_key(&self, key: &str) -> Result<ApiKey, Error> {
+        let uri = format!("{}/keys/{}", self.base_url(), key);
+        self.transport
+            .get(&uri)
+            .headers(self.get_headers())
+            .send_json()
+            .await
+    }
+    /// List the API keys of the application.
+    pub async fn list_api_keys(&self) -> Result<Vec<ApiKey>, Error> {
+        let uri = format!("{}/keys", self.base_url());
+        self.transport
+            .get(&uri)
+            .headers(self.get_headers())
+            .send_json::<ListApiKeysResult>()
+            .await
+            .map(|res| res.keys)
+    }
+    /// Get recommendations, with one result per request, in order.
+    /// ```no_run
+    /// # use algoliasearch::{recommend::{RecommendModel, RecommendRequestBuilder}, Client, Error};
+    /// # #[macro_use] extern crate serde_derive;
+    /// #[derive(Deserialize)]
+    /// struct Product {
+    ///     name: String,
+    /// }
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<Error>> {
+    /// let request = RecommendRequestBuilder::default()
+    ///     .index_name("products")
+    ///     .model(RecommendModel::RelatedProducts {
+    ///         object_id: "42".to_string(),
+    ///     })
+    ///     .max_recommendations(5)
+    ///     .build()
+    ///     .unwrap();
+    /// let results = Client::default()
+    ///     .get_recommendations::<Product>(vec![request])
+    ///     .await?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn get_recommendations<T: DeserializeOwned>(
+        &self,
+        requests: Vec<RecommendRequest>,
+    ) -> Result<Vec<RecommendResult<T>>, Error> {
+        let (application_id, _) = self.credentials();
+        let uri = format!(
+            "https://{}-dsn.algolia.net/1/indexes/*/recommendations",
+            application_id
+        );
+        self.transport
+            .post(&uri)
+            .headers(self.get_headers())
+            .json(&RecommendRequests {
+                requests: &requests,
+            })
+            .send_json::<RecommendResults<T>>()
+            .await
+            .map(|res| res.results)
+    }
+    /// Wait until an operation on an API key is visible. Key operations are
+    /// eventually consistent: a key may not be usable right after being added.
+    /// Fails with [Error::Timeout](../error/enum.Error.html#variant.Timeout) when the
+    /// operation is still not visible after about a minute.
+    pub async fn wait_for_api_key(
+        &self,
+        key: &str,
+        operation: ApiKeyOperation<'_>,
+    ) -> Result<(), Error> {
+        self.wait_for_api_key_attempts(key, operation, WAIT_FOR_API_KEY_MAX_ATTEMPTS)
+            .await
+    }
+    // Poll the key up to `max_attempts` times.
+    async fn wait_for_api_key_attempts(
+        &self,
+        key: &str,
+        operation: ApiKeyOperation<'_>,
+        max_attempts: u64,
+    ) -> Result<(), Error> {
+        for attempt in 1..=max_attempts {
+            let done = match (self.get_api_key(key).await, operation) {
+                (Ok(_), ApiKeyOperation::Add) => true,
+                (Ok(current), ApiKeyOperation::Update(settings)) => {
+                    current.has_same_settings(settings)
+                }
+                (Err(Error::Api { status: 404, .. }), ApiKeyOperation::Delete) => true,
+                (Ok(_), ApiKeyOperation::Delete) => false,
+                (Err(Error::Api { status: 404, .. }), _) => false,
+                (Err(err), _) => return Err(err),
+            };
+            if done {
+                return Ok(());
+            }
+            if attempt < max_attempts {
+                runtime::sleep(Duration::from_millis(100 * attempt.min(10))).await;
+            }
         }
+        Err(Error::Timeout(format!(
+            "API key operation not visible after {} attempts",
+            max_attempts
+        )))
+    }
+    /// Generate a [secured API key](https://www.algolia.com/doc/guides/security/api-keys/how-to/user-restricted-access-to-data/)
+    /// from a search API key. The key is computed locally, without any call to the API.
+    /// ```
+    /// # use algoliasearch::{api_key::SecuredKeyRestrictionsBuilder, filters, Client};
+    /// # use chrono::{Duration, Utc};
+    /// let restrictions = SecuredKeyRestrictionsBuilder::default()
+    ///     .filters(filters::parse("tenant:42").unwrap())
+    ///     .valid_until(Utc::now() + Duration::hours(1))
+    ///     .build()
+    ///     .unwrap();
+    /// let key = Client::generate_secured_api_key("SEARCH_API_KEY", &restrictions);
+    /// let decoded = Client::get_secured_api_key_restrictions(&key).unwrap();
+    /// assert_eq!(decoded.filters, restrictions.filters);
+    /// ```
+    pub fn generate_secured_api_key(
+        parent_api_key: &str,
+        restrictions: &SecuredKeyRestrictions,
+    ) -> String {
+        api_key::generate_secured_api_key(parent_api_key, restrictions)
+    }
+    /// Decode the restrictions of a secured API key.
+    pub fn get_secured_api_key_restrictions(
+        secured_api_key: &str,
+    ) -> Result<SecuredKeyRestrictions, Error> {
+        api_key::get_secured_api_key_restrictions(secured_api_key)
+    }
+    /// Time left before a secured API key expires, negative once expired,
+    /// `None` if it has no expiration date.
+    pub fn get_secured_api_key_remaining_validity(
+        secured_api_key: &str,
+    ) -> Result<Option<chrono::Duration>, Error> {
+        Ok(api_key::get_secured_api_key_restrictions(secured_api_key)?.remaining_validity())
+    }
+    /// Add or replace entries of a dictionary.
+    /// ```no_run
+    /// # use algoliasearch::{dictionaries::StopwordEntry, Client, Error};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<Error>> {
+    /// let entry = StopwordEntry {
+    ///     object_id: "down".to_string(),
+    ///     language: "en".to_string(),
+    ///     word: "down".to_string(),
+    ///     state: None,
+    ///     entry_type: None,
+    /// };
+    /// Client::default().save_dictionary_entries(&[entry]).await?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn save_dictionary_entries<E: DictionaryEntry>(
+        &self,
+        entries: &[E],
+    ) -> Result<DictionaryTaskResult, Error> {
+        self.dictionary_batch(
+            E::DICTIONARY,
+            DictionaryBatch {
+                clear_existing_dictionary_entries: false,
+                requests: entries.iter().map(DictionaryOperation::AddEntry).collect(),
+            },
+        )
+        .await
+    }
+    /// Replace all the custom entries of a dictionary.
+    pub async fn replace_dictionary_entries<E: DictionaryEntry>(
+        &self,
+        entries: &[E],
+    ) -> Result<DictionaryTaskResult, Error> {
+        self.dictionary_batch(
+            E::DICTIONARY,
+            DictionaryBatch {
+                clear_existing_dictionary_entries: true,
+                requests: entries.iter().map(DictionaryOperation::AddEntry).collect(),
+            },
+        )
+        .await
+    }
+    /// Delete entries of a dictionary.
+    pub async fn delete_dictionary_entries(
+        &self,
+        dictionary: Dictionary,
+        object_ids: &[&str],
+    ) -> Result<DictionaryTaskResult, Error> {
+        self.dictionary_batch::<()>(
+            dictionary,
+            DictionaryBatch {
+                clear_existing_dictionary_entries: false,
+                requests: object_ids
+                    .iter()
+                    .map(|object_id| DictionaryOperation::DeleteEntry { object_id })
+                    .collect(),
+            },
+        )
+        .await
+    }
+    /// Delete all the custom entries of a dictionary.
+    pub async fn clear_dictionary_entries(
+        &self,
+        dictionary: Dictionary,
+    ) -> Result<DictionaryTaskResult, Error> {
+        self.dictionary_batch::<()>(
+            dictionary,
+            DictionaryBatch {
+                clear_existing_dictionary_entries: true,
+                requests: vec![],
+            },
+        )
+        .await
+    }
+    /// Search the entries of a dictionary.
+    /// ```no_run
+    /// # use algoliasearch::{dictionaries::{DictionarySearchQueryBuilder, PluralEntry}, Client, Error};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<Error>> {
+    /// let query = DictionarySearchQueryBuilder::default()
+    ///     .query("mouse")
+    ///     .language("en".to_string())
+    ///     .build()
+    ///     .unwrap();
+    /// let res = Client::default()
+    ///     .search_dictionary_entries::<PluralEntry>(&query)
+    ///     .await?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn search_dictionary_entries<E: DictionaryEntry>(
+        &self,
+        query: &DictionarySearchQuery,
+    ) -> Result<DictionarySearchResult<E>, Error> {
+        let uri = format!(
+            "{}/dictionaries/{}/search",
+            self.base_url(),
+            E::DICTIONARY.as_str()
+        );
+        self.transport
+            .post(&uri)
+            .headers(self.get_headers())
+            .json(query)
+            .send_json()
+            .await
+    }
+    /// Get the settings of the dictionaries.
+    pub async fn get_dictionary_settings(&self) -> Result<DictionarySettings, Error> {
+        let uri = format!("{}/dictionaries/*/settings", self.base_url());
+        self.transport
+            .get(&uri)
+            .headers(self.get_headers())
+            .send_json()
+            .await
+    }
+    /// Set the settings of the dictionaries.
+    pub async fn set_dictionary_settings(
+        &self,
+        settings: &DictionarySettings,
+    ) -> Result<DictionaryTaskResult, Error> {
+        let uri = format!("{}/dictionaries/*/settings", self.base_url());
+        self.transport
+            .put(&uri)
+            .headers(self.get_headers())
+            .json(settings)
+            .send_json()
+            .await
+    }
+    // Send a batch of operations on a dictionary.
+    async fn dictionary_batch<E: serde::Serialize>(
+        &self,
+        dictionary: Dictionary,
+        batch: DictionaryBatch<'_, E>,
+    ) -> Result<DictionaryTaskResult, Error> {
+        let uri = format!(
+            "{}/dictionaries/{}/batch",
+            self.base_url(),
+            dictionary.as_str()
+        );
+        self.transport
+            .post(&uri)
+            .headers(self.get_headers())
+            .json(&batch)
+            .send_json()
+            .await
+    }
+    /// Get the latest logs of the application, 10 by default and at most 1000.
+    /// ```no_run
+    /// # use algoliasearch::{logs::LogType, Client, Error};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<Error>> {
+    /// let logs = Client::default()
+    ///     .get_logs(None, Some(100), Some(LogType::Error), Some("products"))
+    ///     .await?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn get_logs(
+        &self,
+        offset: Option<u64>,
+        length: Option<u64>,
+        log_type: Option<LogType>,
+        index_name: Option<&str>,
+    ) -> Result<Vec<LogEntry>, Error> {
+        let uri = format!("{}/logs", self.base_url());
+        self.transport
+            .get(&uri)
+            .headers(self.get_headers())
+            .query(&LogsQuery {
+                offset,
+                length,
+                log_type,
+                index_name,
+            })
+            .send_json::<LogsResult>()
+            .await
+            .map(|res| res.logs)
+    }
+    /// Stream the logs of the application, fetching them by pages of 1000.
+    /// ```no_run
+    /// # use algoliasearch::{Client, Error};
+    /// # use futures::{pin_mut, TryStreamExt};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<Error>> {
+    /// let client = Client::default();
+    /// let logs = client.get_logs_stream(None, None);
+    /// pin_mut!(logs);
+    /// while let Some(log) = logs.try_next().await? {
+    ///     dbg!(log.answer_code);
+    /// }
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub fn get_logs_stream<'a>(
+        &'a self,
+        log_type: Option<LogType>,
+        index_name: Option<&'a str>,
+    ) -> impl Stream<Item = Result<LogEntry, Error>> + 'a {
+        const PAGE_LENGTH: u64 = 1000;
+        stream::try_unfold(Some(0), move |offset| {
+            let log_type = log_type.clone();
+            async move {
+                let offset = match offset {
+                    Some(offset) => offset,
+                    None => return Ok(None),
+                };
+                let logs = self
+                    .get_logs(Some(offset), Some(PAGE_LENGTH), log_type, index_name)
+                    .await?;
+                let length = logs.len() as u64;
+                if length == 0 {
+                    return Ok(None);
+                }
+                let next = if length < PAGE_LENGTH {
+                    None
+                } else {
+                    Some(offset + length)
+                };
+                Ok::<_, Error>(Some((stream::iter(logs.into_iter().map(Ok)), next)))
+            }
+        })
+        .try_flatten()
+    }
+    /// List the clusters of the application.
+    pub async fn list_clusters(&self) -> Result<Vec<Cluster>, Error> {
+        let uri = format!("{}/clusters", self.base_url());
+        self.transport
+            .get(&uri)
+            .headers(self.get_headers())
+            .send_json::<ListClustersResult>()
+            .await
+            .map(|res| res.clusters)
+    }
+    /// Assign a user ID to a cluster, moving its records if it already had one.
+    /// ```no_run
+    /// # use algoliasearch::{Client, Error};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<Error>> {
+    /// let client = Client::default();
+    /// client.assign_user_id("user-42", "c11-test").await?;
+    /// let index = client.init_index::<()>("contacts").with_user_id("user-42")?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn assign_user_id(
+        &self,
+        user_id: &str,
+        cluster: &str,
+    ) -> Result<AssignUserIdResult, Error> {
+        let uri = format!("{}/clusters/mapping", self.base_url());
+        self.transport
+            .post(&uri)
+            .headers(self.get_headers())
+            .header(crate::USER_ID_HEADER, user_id)
+            .json(&AssignUserIdBody { cluster })
+            .send_json()
+            .await
+    }
+    /// Assign user IDs to a cluster.
+    pub async fn batch_assign_user_ids(
+        &self,
+        user_ids: &[&str],
+        cluster: &str,
+    ) -> Result<AssignUserIdResult, Error> {
+        let uri = format!("{}/clusters/mapping/batch", self.base_url());
+        self.transport
+            .post(&uri)
+            .headers(self.get_headers())
+            .json(&BatchAssignUserIdsBody {
+                cluster,
+                users: user_ids,
+            })
+            .send_json()
+            .await
+    }
+    /// Get a user ID.
+    pub async fn get_user_id(&self, user_id: &str) -> Result<UserId, Error> {
+        let uri = format!(
+            "{}/clusters/mapping/{}",
+            self.base_url(),
+            encode_path_segment(user_id)
+        );
+        self.transport
+            .get(&uri)
+            .headers(self.get_headers())
+            .send_json()
+            .await
+    }
+    /// Get the user IDs with the most records, by cluster name.
+    pub async fn get_top_user_ids(&self) -> Result<HashMap<String, Vec<UserId>>, Error> {
+        let uri = format!("{}/clusters/mapping/top", self.base_url());
+        self.transport
+            .get(&uri)
+            .headers(self.get_headers())
+            .send_json::<TopUserIdsResult>()
+            .await
+            .map(|res| res.top_users)
+    }
+    /// List the user IDs of the application, 20 per page by default.
+    pub async fn list_user_ids(
+        &self,
+        page: Option<u64>,
+        hits_per_page: Option<u64>,
+    ) -> Result<ListUserIdsResult, Error> {
+        let uri = format!("{}/clusters/mapping", self.base_url());
+        self.transport
+            .get(&uri)
+            .headers(self.get_headers())
+            .query(&ListUserIdsQuery {
+                page,
+                hits_per_page,
+            })
+            .send_json()
+            .await
+    }
+    /// Search the user IDs of the application.
+    pub async fn search_user_ids(
+        &self,
+        query: &SearchUserIdsQuery,
+    ) -> Result<SearchUserIdsResult, Error> {
+        let uri = format!("{}/clusters/mapping/search", self.base_url());
+        self.transport
+            .post(&uri)
+            .headers(self.get_headers())
+            .json(query)
+            .send_json()
+            .await
+    }
+    /// Remove a user ID and its records.
+    pub async fn remove_user_id(&self, user_id: &str) -> Result<RemoveUserIdResult, Error> {
+        let uri = format!("{}/clusters/mapping", self.base_url());
+        self.transport
+            .delete(&uri)
+            .headers(self.get_headers())
+            .header(crate::USER_ID_HEADER, user_id)
+            .send_json()
+            .await
+    }
+    /// Check whether user IDs are being migrated, with the migrations when
+    /// `get_clusters` is true.
+    pub async fn has_pending_mappings(
+        &self,
+        get_clusters: bool,
+    ) -> Result<PendingMappingsResult, Error> {
+        let uri = format!("{}/clusters/mapping/pending", self.base_url());
+        self.transport
+            .get(&uri)
+            .headers(self.get_headers())
+            .query(&PendingMappingsQuery { get_clusters })
+            .send_json()
+            .await
+    }
+    // Client without credentials, sending its requests with reqwest.
+    fn with_default_transport() -> Client {
+        let transport: Arc<dyn Transport> = Arc::new(ReqwestTransport::default());
+        Client {
+            application_id: None,
+            api_key: None,
+            transport: transport.clone(),
+            base_transport: transport,
+            rate_limiter: None,
+            #[cfg(feature = "gzip")]
+            gzip_min_body_size: None,
+            credentials_provider: None,
+            interceptors: vec![],
+        }
+    }
+    // Wrap the transport with the compression, the rate limiter, the interceptors,
+    // then the credentials provider.
+    fn chain_interceptors(mut self) -> Client {
+        let mut transport = self.base_transport.clone();
+        #[cfg(feature = "gzip")]
+        if let Some(min_body_size) = self.gzip_min_body_size {
+            transport = Arc::new(crate::compression::GzipTransport {
+                transport,
+                min_body_size,
+            });
+        }
+        if let Some(limiter) = &self.rate_limiter {
+            transport = Arc::new(RateLimitedTransport {
+                transport,
+                limiter: limiter.clone(),
+            });
+        }
+        if !self.interceptors.is_empty() {
+            transport = Arc::new(InterceptedTransport {
+                transport,
+                interceptors: self.interceptors.clone(),
+            });
+        }
+        if let Some(provider) = &self.credentials_provider {
+            transport = Arc::new(CredentialsTransport {
+                transport,
+                provider: provider.clone(),
+            });
+        }
+        self.transport = transport;
+        self
+    }
+    // Application id and API key, panics when they are missing.
+    // The API key is empty when a credentials provider sets it on each request.
+    fn credentials(&self) -> (&str, Option<&str>) {
+        match (&self.application_id, &self.api_key) {
+            // the credentials transport sets the key of the provider
+            (Some(application_id), _) if self.credentials_provider.is_some() => {
+                (application_id, None)
+            }
+            (Some(application_id), Some(api_key)) => (application_id, Some(api_key)),
+            _ => panic!("application_id and/or api_key are not initialized"),
+        }
+    }
+    // Url of the application-level endpoints.
+    fn base_url(&self) -> String {
+        format!("https://{}.algolia.net/1", self.credentials().0)
+    }
+    // Build authentication headers.
+    fn get_headers(&self) -> HeaderMap {
+        let (application_id, api_key) = self.credentials();
+        auth_headers(application_id, api_key)
     }
 }
 
@@ -66,6 +839,7 @@ impl Default for Client {
         Client {
             application_id: env::var(ALGOLIA_APPLICATION_ID_VARIABLE).ok(),
             api_key: env::var(ALGOLIA_API_KEY_VARIABLE).ok(),
+            ..Client::with_default_transport()
         }
     }
 }
@@ -73,6 +847,8 @@ impl Default for Client {
 #[cfg(test)]
 mod client_tests {
     use super::*;
+    use crate::transport::{HttpRequest, HttpResponse, Method};
+    use futures::future::BoxFuture;
 
     struct User;
     #[test]
@@ -94,4 +870,141 @@ mod client_tests {
     fn test_missing_application_id_and_api_key() {
         Client::default().init_index::<User>("will fail");
     }
+
+    #[derive(Debug, Default)]
+    struct Recorder(std::sync::Mutex<Vec<HttpRequest>>);
+
+    impl Transport for Arc<Recorder> {
+        fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+            self.0.lock().unwrap().push(request);
+            Box::pin(async {
+                Ok(HttpResponse {
+                    status: 200,
+                    headers: HeaderMap::new(),
+                    body: br#"{"hits":[{"name":"phone"}],"nbHits":1,"page":0,"nbPages":1,"hitsPerPage":20,"processingTimeMS":1,"exhaustiveNbHits":true,"query":"phone","params":"query=phone"}"#.to_vec(),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transport() {
+        let recorder = Arc::new(Recorder::default());
+        let index = Client::new("APP", "KEY")
+            .transport(recorder.clone())
+            .init_index::<serde_json::Value>("products");
+        let res = index.search("phone").await.unwrap();
+        assert_eq!(res.hits[0]["name"], "phone");
+        let requests = recorder.0.lock().unwrap();
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(
+            requests[0].url,
+            "https://APP-dsn.algolia.net/1/indexes/products/query"
+        );
+        assert_eq!(requests[0].headers[crate::API_KEY_HEADER], "KEY");
+        assert_eq!(
+            requests[0].body.as_deref(),
+            Some(&br#"{"params":"query=phone"}"#[..])
+        );
+    }
+
+    #[test]
+    fn test_regions() {
+        let client = Client::new("APP", "KEY");
+        assert_eq!(
+            client.init_personalization(Region::De).base_url,
+            "https://personalization.eu.algolia.com/1"
+        );
+        assert_eq!(
+            client.init_query_suggestions(Region::Us).base_url,
+            "https://query-suggestions.us.algolia.com/1"
+        );
+        assert_eq!(
+            client.init_analytics(Some(Region::De)).base_url,
+            "https://analytics.de.algolia.com/2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_user_id() {
+        let recorder = Arc::new(Recorder::default());
+        let index = Client::new("APP", "KEY")
+            .transport(recorder.clone())
+            .init_index::<serde_json::Value>("products");
+        assert!(matches!(
+            index.with_user_id("user\n42"),
+            Err(Error::InvalidHeader(_))
+        ));
+        let index = index.with_user_id("user-42").unwrap();
+        index.search("phone").await.unwrap();
+        let requests = recorder.0.lock().unwrap();
+        assert_eq!(requests[0].headers[crate::USER_ID_HEADER], "user-42");
+    }
+
+    #[tokio::test]
+    async fn test_get_user_id() {
+        let recorder = Arc::new(Recorder::default());
+        let client = Client::new("APP", "KEY").transport(recorder.clone());
+        // the recorded answer isn't a user ID
+        assert!(client.get_user_id("team/a b?#").await.is_err());
+        let requests = recorder.0.lock().unwrap();
+        assert_eq!(
+            requests[0].url,
+            "https://APP.algolia.net/1/clusters/mapping/team%2Fa%20b%3F%23"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_credentials_provider() {
+        let recorder = Arc::new(Recorder::default());
+        let index = Client::default()
+            .application_id("APP")
+            .transport(recorder.clone())
+            .credentials_provider(crate::credentials::StaticCredentials(
+                "ROTATED_KEY".to_string(),
+            ))
+            .init_index::<serde_json::Value>("products");
+        assert_eq!(index.api_key, None);
+        index.search("phone").await.unwrap();
+        // the provider is used instead of the API key
+        let index = Client::new("APP", "STATIC_KEY")
+            .transport(recorder.clone())
+            .credentials_provider(crate::credentials::StaticCredentials(
+                "ROTATED_KEY".to_string(),
+            ))
+            .init_index::<serde_json::Value>("products");
+        index.search("phone").await.unwrap();
+        let requests = recorder.0.lock().unwrap();
+        for request in requests.iter() {
+            assert_eq!(request.headers[crate::API_KEY_HEADER], "ROTATED_KEY");
+        }
+    }
+
+    #[derive(Debug)]
+    struct NotFound;
+
+    impl Transport for NotFound {
+        fn send(&self, _: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+            Box::pin(async {
+                Ok(HttpResponse {
+                    status: 404,
+                    headers: HeaderMap::new(),
+                    body: br#"{"message":"Key does not exist"}"#.to_vec(),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_api_key_timeout() {
+        let client = Client::new("APP", "KEY").transport(NotFound);
+        let res = client
+            .wait_for_api_key_attempts("missing", ApiKeyOperation::Add, 3)
+            .await;
+        assert!(matches!(res, Err(Error::Timeout(_))));
+        client
+            .wait_for_api_key_attempts("missing", ApiKeyOperation::Delete, 3)
+            .await
+            .unwrap();
+    }
 }
@@ -0,0 +1,187 @@
+//! [Multi-cluster management](https://www.algolia.com/doc/guides/scaling/managing-multiple-clusters-mcm/),
+//! where the records of each user ID live on one of the clusters of the application.
+//! See [Client::assign_user_id](../client/struct.Client.html#method.assign_user_id) and
+//! [Index::with_user_id](../index/struct.Index.html#method.with_user_id).
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// A cluster of the application
+pub struct Cluster {
+    /// Name
+    pub cluster_name: String,
+    /// Number of records
+    pub nb_records: u64,
+    #[serde(rename = "nbUserIDs")]
+    /// Number of user IDs
+    pub nb_user_ids: u64,
+    /// Size of the data (bytes)
+    pub data_size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ListClustersResult {
+    pub(crate) clusters: Vec<Cluster>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// A user ID and its cluster
+pub struct UserId {
+    #[serde(rename = "userID")]
+    /// User ID
+    pub user_id: String,
+    /// Cluster of the user ID
+    pub cluster_name: String,
+    /// Number of records of the user ID
+    pub nb_records: u64,
+    /// Size of the data of the user ID (bytes)
+    pub data_size: u64,
+}
+
+#[derive(Serialize)]
+pub(crate) struct AssignUserIdBody<'a> {
+    pub(crate) cluster: &'a str,
+}
+
+#[derive(Serialize)]
+pub(crate) struct BatchAssignUserIdsBody<'a> {
+    pub(crate) cluster: &'a str,
+    pub(crate) users: &'a [&'a str],
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Assign user ID result
+pub struct AssignUserIdResult {
+    /// Date of the assignment
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Remove user ID result
+pub struct RemoveUserIdResult {
+    /// Date of the removal
+    pub deleted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TopUserIdsResult {
+    pub(crate) top_users: HashMap<String, Vec<UserId>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ListUserIdsQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) page: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) hits_per_page: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// List user IDs result
+pub struct ListUserIdsResult {
+    #[serde(rename = "userIDs")]
+    /// User IDs of the page
+    pub user_ids: Vec<UserId>,
+    /// Page
+    pub page: u64,
+    /// Number of user IDs per page
+    pub hits_per_page: u64,
+}
+
+#[derive(Clone, Builder, Debug, Default, Serialize)]
+#[builder(default)]
+#[serde(rename_all = "camelCase")]
+/// Search in the user IDs
+pub struct SearchUserIdsQuery {
+    #[builder(setter(into))]
+    /// Query
+    query: String,
+    #[builder(setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Only search the user IDs of this cluster
+    cluster_name: Option<String>,
+    #[builder(setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Page
+    page: Option<u64>,
+    #[builder(setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Number of hits per page
+    hits_per_page: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Search user IDs result
+pub struct SearchUserIdsResult {
+    /// Hits
+    pub hits: Vec<UserId>,
+    /// Number of hits
+    pub nb_hits: u64,
+    /// Page
+    pub page: u64,
+    /// Number of hits per page
+    pub hits_per_page: u64,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    /// Date of the last update of the user IDs index
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PendingMappingsQuery {
+    pub(crate) get_clusters: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+/// User IDs being migrated to a cluster
+pub struct PendingMapping {
+    #[serde(rename = "clusterName")]
+    /// Destination cluster
+    pub cluster_name: String,
+    /// User IDs
+    pub users: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+/// Pending mappings result
+pub struct PendingMappingsResult {
+    /// Are user IDs being migrated?
+    pub pending: bool,
+    #[serde(default)]
+    /// Migrations, only set when requested
+    pub clusters: Option<Vec<PendingMapping>>,
+}
+
+#[cfg(test)]
+mod clusters_tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn test_deserialize() {
+        let res = serde_json::from_str::<TopUserIdsResult>(
+            r#"{"topUsers":{"c1-test":[{"userID":"user-1","clusterName":"c1-test","nbRecords":42,"dataSize":1024}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(res.top_users["c1-test"][0].user_id, "user-1");
+        let res = serde_json::from_str::<SearchUserIdsResult>(
+            r#"{"hits":[{"userID":"user-1","clusterName":"c1-test","nbRecords":42,"dataSize":1024,"objectID":"user-1"}],"nbHits":1,"page":0,"hitsPerPage":20,"updatedAt":1514563200}"#,
+        )
+        .unwrap();
+        assert_eq!(res.updated_at.timestamp(), 1514563200);
+        let res = serde_json::from_str::<PendingMappingsResult>(
+            r#"{"pending":true,"clusters":[{"clusterName":"c2-test","users":["user-2"]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(res.clusters.unwrap()[0].users, vec!["user-2".to_string()]);
+    }
+}
@@ -0,0 +1,123 @@
+//! Gzip compression of the bodies of writes, see
+//! [Client::gzip](../client/struct.Client.html#method.gzip).
+use std::{io::Write, sync::Arc};
+
+use flate2::{write::GzEncoder, Compression};
+use futures::future::BoxFuture;
+use reqwest::header::{HeaderValue, CONTENT_ENCODING};
+
+use crate::{
+    error::Error,
+    request::CallType,
+    transport::{HttpRequest, HttpResponse, Transport},
+};
+
+// Transport compressing the large bodies of writes before another one sends them.
+#[derive(Debug)]
+pub(crate) struct GzipTransport {
+    pub(crate) transport: Arc<dyn Transport>,
+    pub(crate) min_body_size: usize,
+}
+
+impl Transport for GzipTransport {
+    fn send(&self, mut request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+        let compress = CallType::of(&request) == CallType::Write
+            && !request.headers.contains_key(CONTENT_ENCODING)
+            && request
+                .body
+                .as_ref()
+                .is_some_and(|body| body.len() >= self.min_body_size);
+        if compress {
+            if let Some(body) = request.body.take() {
+                match gzip(&body) {
+                    Ok(compressed) => {
+                        request.body = Some(compressed);
+                        request
+                            .headers
+                            .insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+                    }
+                    Err(error) => {
+                        return Box::pin(async { Err(Error::Transport(Box::new(error))) })
+                    }
+                }
+            }
+        }
+        self.transport.send(request)
+    }
+}
+
+fn gzip(body: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(body.len() / 4), Compression::fast());
+    encoder.write_all(body)?;
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod compression_tests {
+    use super::*;
+    use std::{io::Read, sync::Mutex};
+
+    use flate2::read::GzDecoder;
+
+    use crate::transport::{HeaderMap, Method};
+
+    #[derive(Debug, Default)]
+    struct Recorder(Mutex<Vec<HttpRequest>>);
+
+    impl Transport for Recorder {
+        fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+            self.0.lock().unwrap().push(request);
+            Box::pin(async {
+                Ok(HttpResponse {
+                    status: 200,
+                    headers: HeaderMap::new(),
+                    body: vec![],
+                })
+            })
+        }
+    }
+
+    fn request(path: &str, body: &[u8]) -> HttpRequest {
+        HttpRequest {
+            method: Method::POST,
+            url: format!("https://APP.algolia.net{}", path),
+            headers: HeaderMap::new(),
+            body: Some(body.to_vec()),
+            timeout: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gzip() {
+        let recorder = Arc::new(Recorder::default());
+        let transport = GzipTransport {
+            transport: recorder.clone(),
+            min_body_size: 100,
+        };
+        let batch = br#"{"action":"addObject","body":{"name":"Bernardo"}}"#.repeat(10);
+        transport
+            .send(request("/1/indexes/users/batch", &batch))
+            .await
+            .unwrap();
+        transport
+            .send(request("/1/indexes/users/batch", b"{}"))
+            .await
+            .unwrap();
+        transport
+            .send(request("/1/indexes/users/query", &batch))
+            .await
+            .unwrap();
+
+        let requests = recorder.0.lock().unwrap();
+        assert_eq!(requests[0].headers[CONTENT_ENCODING], "gzip");
+        let mut decompressed = vec![];
+        GzDecoder::new(&requests[0].body.as_ref().unwrap()[..])
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, batch);
+        for request in &requests[1..] {
+            assert!(!request.headers.contains_key(CONTENT_ENCODING));
+        }
+        assert_eq!(requests[2].body.as_ref().unwrap(), &batch);
+    }
+}
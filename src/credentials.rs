@@ -0,0 +1,193 @@
+//! Sources of the API key, consulted on each request, so the key can be rotated
+//! without initializing the indices again, see
+//! [Client::credentials_provider](../client/struct.Client.html#method.credentials_provider).
+//! ```no_run
+//! # use algoliasearch::{credentials::FileCredentials, Client};
+//! // the key is read again when the file changes, e.g. when a secret is rotated
+//! let client = Client::default()
+//!     .application_id("APP_ID")
+//!     .credentials_provider(FileCredentials::new("/run/secrets/algolia-api-key"));
+//! ```
+use std::{
+    env, fmt, fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+use futures::future::BoxFuture;
+use reqwest::header::HeaderValue;
+
+use crate::{
+    error::Error,
+    transport::{HttpRequest, HttpResponse, Transport},
+};
+
+/// Source of the API key. It is consulted on each request, so a provider fetching
+/// the key from a remote source should cache it.
+/// ```
+/// # use algoliasearch::{credentials::CredentialsProvider, Error};
+/// # use futures::future::BoxFuture;
+/// #[derive(Debug)]
+/// struct Vault;
+///
+/// impl CredentialsProvider for Vault {
+///     fn api_key(&self) -> BoxFuture<'_, Result<String, Error>> {
+///         Box::pin(async { Ok("KEY_FROM_VAULT".to_string()) })
+///     }
+/// }
+/// ```
+pub trait CredentialsProvider: fmt::Debug + Send + Sync {
+    /// Current API key.
+    fn api_key(&self) -> BoxFuture<'_, Result<String, Error>>;
+}
+
+#[derive(Clone, Debug)]
+/// API key that never changes
+pub struct StaticCredentials(pub String);
+
+impl CredentialsProvider for StaticCredentials {
+    fn api_key(&self) -> BoxFuture<'_, Result<String, Error>> {
+        Box::pin(async move { Ok(self.0.clone()) })
+    }
+}
+
+#[derive(Clone, Debug)]
+/// API key read from an environment variable on each request
+pub struct EnvCredentials {
+    variable: String,
+}
+
+impl EnvCredentials {
+    /// Read the key from `variable`, e.g. `ALGOLIA_API_KEY`.
+    pub fn new(variable: &str) -> EnvCredentials {
+        EnvCredentials {
+            variable: variable.to_string(),
+        }
+    }
+}
+
+impl CredentialsProvider for EnvCredentials {
+    fn api_key(&self) -> BoxFuture<'_, Result<String, Error>> {
+        Box::pin(async move {
+            env::var(&self.variable)
+                .map_err(|err| Error::Credentials(format!("{}: {}", self.variable, err)))
+        })
+    }
+}
+
+/// API key read from a file, and read again when the modification date of the file changes.
+/// The file is checked at most once per [check_interval](#method.check_interval),
+/// so requests rarely wait for the file system.
+pub struct FileCredentials {
+    path: PathBuf,
+    check_interval: Duration,
+    // key, the modification date of the file it was read from, and when it was checked
+    cached: Mutex<Option<(String, SystemTime, Instant)>>,
+}
+
+impl FileCredentials {
+    /// Read the key from the file at `path`, whitespace trimmed.
+    pub fn new(path: impl Into<PathBuf>) -> FileCredentials {
+        FileCredentials {
+            path: path.into(),
+            check_interval: Duration::from_secs(1),
+            cached: Mutex::new(None),
+        }
+    }
+    /// Minimum time between two checks of the file, 1 second by default.
+    pub fn check_interval(mut self, check_interval: Duration) -> FileCredentials {
+        self.check_interval = check_interval;
+        self
+    }
+    fn read(&self) -> Result<String, Error> {
+        let mut cached = self.cached.lock().unwrap();
+        if let Some((api_key, _, checked_at)) = &*cached {
+            if checked_at.elapsed() < self.check_interval {
+                return Ok(api_key.clone());
+            }
+        }
+        let error =
+            |err: std::io::Error| Error::Credentials(format!("{}: {}", self.path.display(), err));
+        let modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(error)?;
+        let api_key = match &*cached {
+            Some((api_key, read_at, _)) if *read_at == modified => api_key.clone(),
+            _ => fs::read_to_string(&self.path)
+                .map_err(error)?
+                .trim()
+                .to_string(),
+        };
+        *cached = Some((api_key.clone(), modified, Instant::now()));
+        Ok(api_key)
+    }
+}
+
+impl fmt::Debug for FileCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileCredentials")
+            .field("path", &self.path)
+            .field("check_interval", &self.check_interval)
+            .finish()
+    }
+}
+
+impl CredentialsProvider for FileCredentials {
+    fn api_key(&self) -> BoxFuture<'_, Result<String, Error>> {
+        Box::pin(async move { self.read() })
+    }
+}
+
+// Transport setting the API key of the provider on the requests sent by another one.
+#[derive(Debug)]
+pub(crate) struct CredentialsTransport {
+    pub(crate) transport: Arc<dyn Transport>,
+    pub(crate) provider: Arc<dyn CredentialsProvider>,
+}
+
+impl Transport for CredentialsTransport {
+    fn send(&self, mut request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+        Box::pin(async move {
+            let api_key = self.provider.api_key().await?;
+            let api_key = HeaderValue::from_str(&api_key)
+                .map_err(|_| Error::InvalidHeader(crate::API_KEY_HEADER.to_string()))?;
+            request.headers.insert(crate::API_KEY_HEADER, api_key);
+            self.transport.send(request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod credentials_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_env() {
+        let provider = EnvCredentials::new("ALGOLIA_TEST_ROTATED_API_KEY");
+        assert!(provider.api_key().await.is_err());
+        env::set_var("ALGOLIA_TEST_ROTATED_API_KEY", "KEY");
+        assert_eq!(provider.api_key().await.unwrap(), "KEY");
+        env::remove_var("ALGOLIA_TEST_ROTATED_API_KEY");
+    }
+
+    #[tokio::test]
+    async fn test_file() {
+        let path = env::temp_dir().join(format!("algolia-api-key-{}", std::process::id()));
+        fs::write(&path, "FIRST_KEY\n").unwrap();
+        let provider = FileCredentials::new(&path).check_interval(Duration::ZERO);
+        let throttled = FileCredentials::new(&path).check_interval(Duration::from_secs(3600));
+        assert_eq!(provider.api_key().await.unwrap(), "FIRST_KEY");
+        assert_eq!(throttled.api_key().await.unwrap(), "FIRST_KEY");
+
+        fs::write(&path, "SECOND_KEY\n").unwrap();
+        // the modification date may not have changed yet
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(provider.api_key().await.unwrap(), "SECOND_KEY");
+        // not checked again before the interval
+        assert_eq!(throttled.api_key().await.unwrap(), "FIRST_KEY");
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,223 @@
+//! Dictionaries of stop words, plurals and compounds, shared by all the indices
+//! of an application, see [Client::save_dictionary_entries](../client/struct.Client.html#method.save_dictionary_entries).
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+
+enum_str!(Dictionary {
+    Stopwords("stopwords"),
+    Plurals("plurals"),
+    Compounds("compounds"),
+});
+
+/// Entry of a dictionary.
+pub trait DictionaryEntry: Serialize + DeserializeOwned {
+    /// Dictionary holding the entries of this type
+    const DICTIONARY: Dictionary;
+}
+
+enum_str!(EntryState {
+    Enabled("enabled"),
+    Disabled("disabled"),
+});
+
+enum_str!(EntryType {
+    Custom("custom"),
+    Standard("standard"),
+});
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// A stop word, see [removeStopWords](https://www.algolia.com/doc/api-reference/api-parameters/removeStopWords/)
+pub struct StopwordEntry {
+    #[serde(rename = "objectID")]
+    /// Entry ID
+    pub object_id: String,
+    /// Language code
+    pub language: String,
+    /// Stop word
+    pub word: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Disabled entries remove a standard stop word
+    pub state: Option<EntryState>,
+    #[serde(default, rename = "type", skip_serializing)]
+    /// Origin of the entry, only set on entries returned by the API
+    pub entry_type: Option<EntryType>,
+}
+
+impl DictionaryEntry for StopwordEntry {
+    const DICTIONARY: Dictionary = Dictionary::Stopwords;
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// Words considered equivalent, see [ignorePlurals](https://www.algolia.com/doc/api-reference/api-parameters/ignorePlurals/)
+pub struct PluralEntry {
+    #[serde(rename = "objectID")]
+    /// Entry ID
+    pub object_id: String,
+    /// Language code
+    pub language: String,
+    /// Inflected forms of a word
+    pub words: Vec<String>,
+    #[serde(default, rename = "type", skip_serializing)]
+    /// Origin of the entry, only set on entries returned by the API
+    pub entry_type: Option<EntryType>,
+}
+
+impl DictionaryEntry for PluralEntry {
+    const DICTIONARY: Dictionary = Dictionary::Plurals;
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// A compound word, see [decompoundQuery](https://www.algolia.com/doc/api-reference/api-parameters/decompoundQuery/)
+pub struct CompoundEntry {
+    #[serde(rename = "objectID")]
+    /// Entry ID
+    pub object_id: String,
+    /// Language code
+    pub language: String,
+    /// Compound word
+    pub word: String,
+    /// Words the compound is made of
+    pub decomposition: Vec<String>,
+    #[serde(default, rename = "type", skip_serializing)]
+    /// Origin of the entry, only set on entries returned by the API
+    pub entry_type: Option<EntryType>,
+}
+
+impl DictionaryEntry for CompoundEntry {
+    const DICTIONARY: Dictionary = Dictionary::Compounds;
+}
+
+#[derive(Serialize)]
+#[serde(tag = "action", content = "body", rename_all = "camelCase")]
+pub(crate) enum DictionaryOperation<'a, E> {
+    AddEntry(&'a E),
+    DeleteEntry {
+        #[serde(rename = "objectID")]
+        object_id: &'a str,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DictionaryBatch<'a, E> {
+    pub(crate) clear_existing_dictionary_entries: bool,
+    pub(crate) requests: Vec<DictionaryOperation<'a, E>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Result of a change to the dictionaries
+pub struct DictionaryTaskResult {
+    #[serde(rename = "taskID")]
+    /// Task ID
+    pub task_id: u64,
+    /// Date of the change
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Builder, Debug, Default, Serialize)]
+#[builder(default)]
+#[serde(rename_all = "camelCase")]
+/// Search in a dictionary
+pub struct DictionarySearchQuery {
+    #[builder(setter(into))]
+    /// Query
+    query: String,
+    #[builder(setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Page
+    page: Option<u64>,
+    #[builder(setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Number of hits per page
+    hits_per_page: Option<u64>,
+    #[builder(setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Language code
+    language: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", bound = "E: DeserializeOwned")]
+/// Dictionary search result
+pub struct DictionarySearchResult<E> {
+    /// Hits
+    pub hits: Vec<E>,
+    /// Number of hits
+    pub nb_hits: u64,
+    /// Page
+    pub page: u64,
+    /// Number of pages
+    pub nb_pages: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+/// Standard entries to turn off, by language code
+pub struct StandardEntries {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Stop words
+    pub stopwords: Option<HashMap<String, bool>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Plurals
+    pub plurals: Option<HashMap<String, bool>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Compounds
+    pub compounds: Option<HashMap<String, bool>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Dictionary settings
+pub struct DictionarySettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Standard entries to turn off
+    pub disable_standard_entries: Option<StandardEntries>,
+}
+
+#[cfg(test)]
+mod dictionaries_tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn test_serialize_batch() {
+        let entry = CompoundEntry {
+            object_id: "kopfschmerzen".to_string(),
+            language: "de".to_string(),
+            word: "kopfschmerzen".to_string(),
+            decomposition: vec!["kopf".to_string(), "schmerzen".to_string()],
+            entry_type: None,
+        };
+        let batch = DictionaryBatch {
+            clear_existing_dictionary_entries: false,
+            requests: vec![
+                DictionaryOperation::AddEntry(&entry),
+                DictionaryOperation::DeleteEntry { object_id: "other" },
+            ],
+        };
+        assert_eq!(
+            serde_json::to_string(&batch).unwrap(),
+            r#"{"clearExistingDictionaryEntries":false,"requests":[{"action":"addEntry","body":{"objectID":"kopfschmerzen","language":"de","word":"kopfschmerzen","decomposition":["kopf","schmerzen"]}},{"action":"deleteEntry","body":{"objectID":"other"}}]}"#
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let res = serde_json::from_str::<DictionarySearchResult<StopwordEntry>>(
+            r#"{"hits":[{"objectID":"the","language":"en","word":"the","state":"disabled","type":"standard"}],"nbHits":1,"page":0,"nbPages":1}"#,
+        )
+        .unwrap();
+        assert_eq!(res.hits[0].state, Some(EntryState::Disabled));
+        assert_eq!(res.hits[0].entry_type, Some(EntryType::Standard));
+        let settings = serde_json::from_str::<DictionarySettings>(
+            r#"{"disableStandardEntries":{"stopwords":{"fr":true}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_string(&settings).unwrap(),
+            r#"{"disableStandardEntries":{"stopwords":{"fr":true}}}"#
+        );
+    }
+}
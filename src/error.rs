@@ -5,6 +5,33 @@ pub enum Error {
     Http(reqwest::Error),
     /// Json serialization/deserialization error
     Json(serde_json::Error),
+    /// Error returned by the API
+    Api {
+        /// Http status code
+        status: u16,
+        /// Error message
+        message: String,
+    },
+    /// Invalid filters expression
+    Filter(crate::filters::FilterError),
+    /// Invalid geo parameter
+    Geo(crate::geo::GeoError),
+    /// Invalid `params` string
+    Params(String),
+    /// Secured API key that can't be decoded
+    InvalidSecuredApiKey,
+    /// Event buffer already shut down
+    BufferClosed,
+    /// Insights event that can't be sent
+    InvalidEvent(String),
+    /// Header that can't be sent
+    InvalidHeader(String),
+    /// Error of a custom transport
+    Transport(Box<dyn std::error::Error + Send + Sync>),
+    /// API key that the credentials provider can't give
+    Credentials(String),
+    /// Operation still not visible after waiting for it
+    Timeout(String),
 }
 
 impl From<reqwest::Error> for Error {
@@ -18,3 +45,15 @@ impl From<serde_json::Error> for Error {
         Error::Json(err)
     }
 }
+
+impl From<crate::filters::FilterError> for Error {
+    fn from(err: crate::filters::FilterError) -> Error {
+        Error::Filter(err)
+    }
+}
+
+impl From<crate::geo::GeoError> for Error {
+    fn from(err: crate::geo::GeoError) -> Error {
+        Error::Geo(err)
+    }
+}
@@ -0,0 +1,423 @@
+//! In-memory cache of searches, see [Index::with_cache](../struct.Index.html#method.with_cache).
+//! Identical searches sent while one is in flight share its response.
+//! ```no_run
+//! # #[macro_use] extern crate serde_derive;
+//! # use std::time::Duration;
+//! # use algoliasearch::{cache::SearchCache, Client, Error};
+//! # #[derive(Debug, Deserialize, Serialize)]
+//! # struct Product;
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<Error>> {
+//! let cache = SearchCache::new(Duration::from_secs(30)).max_entries(500);
+//! let index = Client::default()
+//!     .init_index::<Product>("products")
+//!     .with_cache(&cache);
+//! // a single request is sent
+//! let (first, second) = futures::join!(index.search("phone"), index.search("phone"));
+//! #   Ok(())
+//! # }
+//! ```
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    fmt,
+    future::Future,
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures::{
+    channel::oneshot,
+    future::{FutureExt, Shared},
+};
+use reqwest::header::HeaderValue;
+
+use crate::{error::Error, index::Index, request::RequestOptions};
+
+const DEFAULT_MAX_ENTRIES: usize = 1000;
+const DEFAULT_MAX_BYTES: usize = 10 * 1024 * 1024;
+
+type Body = Arc<Vec<u8>>;
+
+// Everything a search response depends on. The API key is hashed, not to be kept in memory.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct CacheKey {
+    application_id: String,
+    index_name: String,
+    user_id: Option<HeaderValue>,
+    api_key_hash: u64,
+    params: String,
+    options: RequestOptions,
+}
+
+impl CacheKey {
+    pub(crate) fn new<T>(index: &Index<T>, params: String, options: &RequestOptions) -> CacheKey {
+        let mut hasher = DefaultHasher::new();
+        index.api_key.hash(&mut hasher);
+        CacheKey {
+            application_id: index.application_id.clone(),
+            index_name: index.index_name.clone(),
+            user_id: index.user_id.clone(),
+            api_key_hash: hasher.finish(),
+            params,
+            options: options.clone(),
+        }
+    }
+}
+
+enum Entry {
+    // response of the search in flight
+    Pending(Shared<oneshot::Receiver<Body>>),
+    Ready {
+        body: Body,
+        expires_at: Instant,
+        // position in the insertion order
+        sequence: u64,
+    },
+}
+
+struct State {
+    ttl: Duration,
+    max_entries: usize,
+    max_bytes: usize,
+    entries: HashMap<CacheKey, Entry>,
+    // ready entries, oldest first, so they also expire in order
+    order: VecDeque<(CacheKey, u64)>,
+    sequence: u64,
+    len: usize,
+    bytes: usize,
+    // incremented by the invalidations
+    generation: u64,
+}
+
+impl State {
+    fn remove_ready(&mut self, key: &CacheKey, sequence: Option<u64>) {
+        if let Some(Entry::Ready {
+            body,
+            sequence: entry_sequence,
+            ..
+        }) = self.entries.get(key)
+        {
+            if sequence.is_none_or(|sequence| sequence == *entry_sequence) {
+                self.len -= 1;
+                self.bytes -= body.len();
+                self.entries.remove(key);
+            }
+        }
+    }
+
+    fn insert_ready(&mut self, key: CacheKey, body: Body) {
+        self.remove_ready(&key, None);
+        if body.len() > self.max_bytes || self.max_entries == 0 {
+            self.entries.remove(&key);
+            return;
+        }
+        let now = Instant::now();
+        while let Some((oldest, sequence)) = self.order.front().cloned() {
+            let expired = match self.entries.get(&oldest) {
+                Some(Entry::Ready { expires_at, .. }) => *expires_at <= now,
+                _ => true,
+            };
+            if !expired && self.len < self.max_entries && self.bytes + body.len() <= self.max_bytes
+            {
+                break;
+            }
+            self.order.pop_front();
+            self.remove_ready(&oldest, Some(sequence));
+        }
+        self.sequence += 1;
+        self.len += 1;
+        self.bytes += body.len();
+        self.order.push_back((key.clone(), self.sequence));
+        self.entries.insert(
+            key,
+            Entry::Ready {
+                body,
+                expires_at: now + self.ttl,
+                sequence: self.sequence,
+            },
+        );
+    }
+}
+
+enum Lookup {
+    Hit(Body),
+    InFlight(Shared<oneshot::Receiver<Body>>),
+    // the search to send, registered as in flight
+    Miss(oneshot::Sender<Body>, Shared<oneshot::Receiver<Body>>, u64),
+}
+
+/// Cache of search responses, shared by its clones and by the indices using it.
+/// Responses are kept for a TTL, and the oldest ones are evicted first when the cache is full.
+#[derive(Clone)]
+pub struct SearchCache {
+    state: Arc<Mutex<State>>,
+}
+
+impl SearchCache {
+    /// Cache keeping responses for `ttl`, with at most 1000 of them and 10 MiB in total.
+    pub fn new(ttl: Duration) -> SearchCache {
+        SearchCache {
+            state: Arc::new(Mutex::new(State {
+                ttl,
+                max_entries: DEFAULT_MAX_ENTRIES,
+                max_bytes: DEFAULT_MAX_BYTES,
+                entries: HashMap::new(),
+                order: VecDeque::new(),
+                sequence: 0,
+                len: 0,
+                bytes: 0,
+                generation: 0,
+            })),
+        }
+    }
+    /// Maximum number of cached responses.
+    pub fn max_entries(self, max_entries: usize) -> SearchCache {
+        self.state.lock().unwrap().max_entries = max_entries;
+        self
+    }
+    /// Maximum total size of the cached responses, in bytes.
+    pub fn max_bytes(self, max_bytes: usize) -> SearchCache {
+        self.state.lock().unwrap().max_bytes = max_bytes;
+        self
+    }
+    /// Drop the responses of an index. Done by the index after each of its writes.
+    pub fn invalidate(&self, index_name: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.generation += 1;
+        let keys = state
+            .entries
+            .keys()
+            .filter(|key| key.index_name == index_name)
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in keys {
+            state.remove_ready(&key, None);
+            state.entries.remove(&key);
+        }
+    }
+    /// Drop every response.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.generation += 1;
+        state.entries.clear();
+        state.order.clear();
+        state.len = 0;
+        state.bytes = 0;
+    }
+
+    // Cached response of a search, or the response of the same search in flight,
+    // or the response of `fetch`, then cached.
+    pub(crate) async fn get_or_fetch<F>(&self, key: CacheKey, fetch: F) -> Result<Body, Error>
+    where
+        F: Future<Output = Result<Vec<u8>, Error>>,
+    {
+        let (sender, pending, generation) = match self.lookup(&key) {
+            Lookup::Hit(body) => return Ok(body),
+            // the search in flight may fail or be cancelled
+            Lookup::InFlight(pending) => {
+                return match pending.await {
+                    Ok(body) => Ok(body),
+                    Err(_) => fetch.await.map(Arc::new),
+                }
+            }
+            Lookup::Miss(sender, pending, generation) => (sender, pending, generation),
+        };
+        let _guard = PendingGuard {
+            cache: self,
+            key: &key,
+            pending,
+        };
+        let body = Arc::new(fetch.await?);
+        {
+            let mut state = self.state.lock().unwrap();
+            // a write may have made the response stale while in flight
+            if state.generation == generation {
+                state.insert_ready(key.clone(), body.clone());
+            }
+        }
+        let _ = sender.send(body.clone());
+        Ok(body)
+    }
+    // Register the search as in flight, unless it is cached or already in flight.
+    fn lookup(&self, key: &CacheKey) -> Lookup {
+        let mut state = self.state.lock().unwrap();
+        match state.entries.get(key) {
+            Some(Entry::Ready {
+                body, expires_at, ..
+            }) if *expires_at > Instant::now() => return Lookup::Hit(body.clone()),
+            Some(Entry::Pending(pending)) => return Lookup::InFlight(pending.clone()),
+            _ => (),
+        }
+        state.remove_ready(key, None);
+        let (sender, receiver) = oneshot::channel();
+        let pending = receiver.shared();
+        state
+            .entries
+            .insert(key.clone(), Entry::Pending(pending.clone()));
+        Lookup::Miss(sender, pending, state.generation)
+    }
+}
+
+impl fmt::Debug for SearchCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("SearchCache")
+            .field("ttl", &state.ttl)
+            .field("max_entries", &state.max_entries)
+            .field("max_bytes", &state.max_bytes)
+            .field("len", &state.len)
+            .field("bytes", &state.bytes)
+            .finish()
+    }
+}
+
+// Removes the pending entry of a search that failed, was cancelled or was not cached.
+struct PendingGuard<'a> {
+    cache: &'a SearchCache,
+    key: &'a CacheKey,
+    pending: Shared<oneshot::Receiver<Body>>,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.cache.state.lock().unwrap();
+        if let Some(Entry::Pending(pending)) = state.entries.get(self.key) {
+            if pending.ptr_eq(&self.pending) {
+                state.entries.remove(self.key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod cache_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use futures::future::BoxFuture;
+
+    use crate::transport::{HttpRequest, HttpResponse, Transport};
+
+    fn key(index_name: &str, params: &str) -> CacheKey {
+        let index = crate::Client::new("APP", "KEY").init_index::<()>(index_name);
+        CacheKey::new(&index, params.to_string(), &RequestOptions::default())
+    }
+
+    // Counts the searches, answering them with no hits.
+    #[derive(Debug, Default)]
+    struct Searches(AtomicUsize);
+
+    impl Transport for Arc<Searches> {
+        fn send(&self, _: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async {
+                Ok(HttpResponse {
+                    status: 200,
+                    headers: Default::default(),
+                    body: br#"{"hits":[],"page":0,"nbHits":0,"nbPages":0,"hitsPerPage":20,"processingTimeMS":1,"query":"phone","params":"query=phone","exhaustiveNbHits":true}"#.to_vec(),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_ids_are_not_shared() {
+        let searches = Arc::new(Searches::default());
+        let cache = SearchCache::new(Duration::from_secs(60));
+        let index = crate::Client::new("APP", "KEY")
+            .transport(searches.clone())
+            .init_index::<serde_json::Value>("products")
+            .with_cache(&cache);
+        let (alice, bob) = (
+            index.with_user_id("alice").unwrap(),
+            index.with_user_id("bob").unwrap(),
+        );
+        alice.search("phone").await.unwrap();
+        alice.search("phone").await.unwrap();
+        assert_eq!(searches.0.load(Ordering::SeqCst), 1);
+        bob.search("phone").await.unwrap();
+        assert_eq!(searches.0.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_coalescing() {
+        let cache = SearchCache::new(Duration::from_secs(60));
+        let calls = AtomicUsize::new(0);
+        let fetch = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            crate::runtime::sleep(Duration::from_millis(10)).await;
+            Ok(b"{}".to_vec())
+        };
+        let (first, second) = futures::join!(
+            cache.get_or_fetch(key("products", "query=phone"), fetch()),
+            cache.get_or_fetch(key("products", "query=phone"), fetch()),
+        );
+        assert_eq!(first.unwrap(), second.unwrap());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        cache
+            .get_or_fetch(key("products", "query=phone"), fetch())
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        cache.invalidate("products");
+        cache
+            .get_or_fetch(key("products", "query=phone"), fetch())
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_not_cached() {
+        let cache = SearchCache::new(Duration::from_secs(60));
+        let failure = async {
+            Err(Error::Api {
+                status: 500,
+                message: "unavailable".to_string(),
+            })
+        };
+        assert!(cache
+            .get_or_fetch(key("products", "query=phone"), failure)
+            .await
+            .is_err());
+        let body = cache
+            .get_or_fetch(key("products", "query=phone"), async { Ok(b"{}".to_vec()) })
+            .await
+            .unwrap();
+        assert_eq!(*body, b"{}".to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_eviction() {
+        let cache = SearchCache::new(Duration::from_secs(60)).max_entries(2);
+        for params in &["query=a", "query=b", "query=c"] {
+            cache
+                .get_or_fetch(key("products", params), async { Ok(b"{}".to_vec()) })
+                .await
+                .unwrap();
+        }
+        {
+            let state = cache.state.lock().unwrap();
+            assert_eq!(state.len, 2);
+            assert!(!state.entries.contains_key(&key("products", "query=a")));
+        }
+
+        let cache = SearchCache::new(Duration::from_millis(0));
+        cache
+            .get_or_fetch(key("products", "query=a"), async { Ok(b"{}".to_vec()) })
+            .await
+            .unwrap();
+        let failure = async {
+            Err(Error::Api {
+                status: 500,
+                message: "expired".to_string(),
+            })
+        };
+        assert!(cache
+            .get_or_fetch(key("products", "query=a"), failure)
+            .await
+            .is_err());
+    }
+}
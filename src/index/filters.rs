@@ -0,0 +1,843 @@
+//! Parser, validator and printer for the
+//! [filters](https://www.algolia.com/doc/api-reference/api-parameters/filters/) syntax.
+//!
+//! ```
+//! use algoliasearch::filters::{self, Condition, Filter};
+//!
+//! let filter = filters::parse(r#"category:"Sci Fi" AND (price < 10 OR price:20 TO 30)"#).unwrap();
+//! assert!(filter.validate().is_ok());
+//! assert_eq!(
+//!     filter.to_string(),
+//!     r#"category:"Sci Fi" AND (price < 10 OR price:20 TO 30)"#
+//! );
+//!
+//! let err = filters::parse("category:Book AND").unwrap_err();
+//! assert_eq!(err.to_string(), "syntax error at position 17: unexpected end of input, expected a filter");
+//! ```
+use std::{error, fmt, str::FromStr};
+
+use serde::{
+    de::{self, Deserialize, Deserializer},
+    ser::{Serialize, Serializer},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// Numeric comparison operator.
+pub enum Operator {
+    /// `<`
+    Lt,
+    /// `<=`
+    Lte,
+    /// `=`
+    Eq,
+    /// `!=`
+    Neq,
+    /// `>=`
+    Gte,
+    /// `>`
+    Gt,
+}
+
+impl Operator {
+    fn as_str(self) -> &'static str {
+        match self {
+            Operator::Lt => "<",
+            Operator::Lte => "<=",
+            Operator::Eq => "=",
+            Operator::Neq => "!=",
+            Operator::Gte => ">=",
+            Operator::Gt => ">",
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A single filter, the leaf of a [Filter](enum.Filter.html) tree.
+pub enum Condition {
+    /// `attribute:value`, optionally scored with `<score=N>`
+    Facet {
+        #[allow(missing_docs)]
+        attribute: String,
+        #[allow(missing_docs)]
+        value: String,
+        #[allow(missing_docs)]
+        score: Option<u32>,
+    },
+    /// `_tags:value`, optionally scored with `<score=N>`
+    Tag {
+        #[allow(missing_docs)]
+        value: String,
+        #[allow(missing_docs)]
+        score: Option<u32>,
+    },
+    /// `attribute operator value`
+    Numeric {
+        #[allow(missing_docs)]
+        attribute: String,
+        #[allow(missing_docs)]
+        operator: Operator,
+        #[allow(missing_docs)]
+        value: f64,
+    },
+    /// `attribute:lower TO upper`
+    Range {
+        #[allow(missing_docs)]
+        attribute: String,
+        #[allow(missing_docs)]
+        lower: f64,
+        #[allow(missing_docs)]
+        upper: f64,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConditionKind {
+    Facet,
+    Tag,
+    Numeric,
+}
+
+impl Condition {
+    fn kind(&self) -> ConditionKind {
+        match self {
+            Condition::Facet { .. } => ConditionKind::Facet,
+            Condition::Tag { .. } => ConditionKind::Tag,
+            Condition::Numeric { .. } | Condition::Range { .. } => ConditionKind::Numeric,
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Condition::Facet {
+                attribute,
+                value,
+                score,
+            } => {
+                write!(f, "{}:{}", Quoted(attribute), Quoted(value))?;
+                if let Some(score) = score {
+                    write!(f, "<score={}>", score)?;
+                }
+                Ok(())
+            }
+            Condition::Tag { value, score } => {
+                write!(f, "_tags:{}", Quoted(value))?;
+                if let Some(score) = score {
+                    write!(f, "<score={}>", score)?;
+                }
+                Ok(())
+            }
+            Condition::Numeric {
+                attribute,
+                operator,
+                value,
+            } => write!(f, "{} {} {}", Quoted(attribute), operator, value),
+            Condition::Range {
+                attribute,
+                lower,
+                upper,
+            } => write!(f, "{}:{} TO {}", Quoted(attribute), lower, upper),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A parsed `filters` expression.
+pub enum Filter {
+    /// A single filter
+    Condition(Condition),
+    /// `NOT filter`
+    Not(Box<Filter>),
+    /// `filter AND filter AND ...`
+    And(Vec<Filter>),
+    /// `filter OR filter OR ...`
+    Or(Vec<Filter>),
+}
+
+impl Filter {
+    /// Check the expression against the combinations the engine refuses:
+    /// negated groups, `AND` inside an `OR`, and `OR` groups mixing
+    /// facet, tag and numeric filters.
+    pub fn validate(&self) -> Result<(), FilterError> {
+        match self {
+            Filter::Condition(_) => Ok(()),
+            Filter::Not(inner) => match **inner {
+                Filter::Condition(_) => Ok(()),
+                _ => Err(FilterError::NegatedGroup),
+            },
+            Filter::And(filters) => filters.iter().try_for_each(Filter::validate),
+            Filter::Or(filters) => {
+                let mut kinds = vec![];
+                filters
+                    .iter()
+                    .try_for_each(|filter| filter.collect_or_kinds(&mut kinds))?;
+                kinds.dedup();
+                if kinds.len() > 1 {
+                    return Err(FilterError::MixedOrGroup);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn collect_or_kinds(&self, kinds: &mut Vec<ConditionKind>) -> Result<(), FilterError> {
+        match self {
+            Filter::Condition(condition) => kinds.push(condition.kind()),
+            Filter::Not(inner) => match **inner {
+                Filter::Condition(ref condition) => kinds.push(condition.kind()),
+                _ => return Err(FilterError::NegatedGroup),
+            },
+            Filter::And(_) => return Err(FilterError::AndInsideOr),
+            Filter::Or(filters) => filters
+                .iter()
+                .try_for_each(|filter| filter.collect_or_kinds(kinds))?,
+        }
+        Ok(())
+    }
+
+    fn and(mut filters: Vec<Filter>) -> Filter {
+        if filters.len() == 1 {
+            return filters.remove(0);
+        }
+        Filter::And(
+            filters
+                .into_iter()
+                .flat_map(|filter| match filter {
+                    Filter::And(inner) => inner,
+                    filter => vec![filter],
+                })
+                .collect(),
+        )
+    }
+
+    fn or(mut filters: Vec<Filter>) -> Filter {
+        if filters.len() == 1 {
+            return filters.remove(0);
+        }
+        Filter::Or(
+            filters
+                .into_iter()
+                .flat_map(|filter| match filter {
+                    Filter::Or(inner) => inner,
+                    filter => vec![filter],
+                })
+                .collect(),
+        )
+    }
+}
+
+impl From<Condition> for Filter {
+    fn from(condition: Condition) -> Filter {
+        Filter::Condition(condition)
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn write_joined(
+            f: &mut fmt::Formatter,
+            filters: &[Filter],
+            separator: &str,
+            needs_parens: fn(&Filter) -> bool,
+        ) -> fmt::Result {
+            for (i, filter) in filters.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(separator)?;
+                }
+                if needs_parens(filter) {
+                    write!(f, "({})", filter)?;
+                } else {
+                    write!(f, "{}", filter)?;
+                }
+            }
+            Ok(())
+        }
+
+        match self {
+            Filter::Condition(condition) => write!(f, "{}", condition),
+            Filter::Not(inner) => match **inner {
+                Filter::Condition(_) | Filter::Not(_) => write!(f, "NOT {}", inner),
+                _ => write!(f, "NOT ({})", inner),
+            },
+            Filter::And(filters) => write_joined(f, filters, " AND ", |filter| {
+                matches!(filter, Filter::Or(_))
+            }),
+            Filter::Or(filters) => write_joined(f, filters, " OR ", |filter| {
+                matches!(filter, Filter::And(_))
+            }),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = FilterError;
+
+    fn from_str(input: &str) -> Result<Filter, FilterError> {
+        parse(input)
+    }
+}
+
+impl Serialize for Filter {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Filter {
+    fn deserialize<D>(deserializer: D) -> Result<Filter, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+// Quote a string when it would not survive being read back bare.
+struct Quoted<'a>(&'a str);
+
+impl fmt::Display for Quoted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bare = !self.0.is_empty()
+            && !matches!(self.0, "AND" | "OR" | "NOT" | "TO")
+            && self.0.chars().all(is_word_char);
+        if bare {
+            return f.write_str(self.0);
+        }
+        f.write_str("\"")?;
+        for c in self.0.chars() {
+            if c == '"' || c == '\\' {
+                f.write_str("\\")?;
+            }
+            write!(f, "{}", c)?;
+        }
+        f.write_str("\"")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Filter parsing or validation error.
+pub enum FilterError {
+    /// The string is not a valid expression. `position` is a byte offset in the input.
+    Syntax {
+        #[allow(missing_docs)]
+        position: usize,
+        #[allow(missing_docs)]
+        message: String,
+    },
+    /// `NOT` applied to a group, e.g. `NOT (a:b OR c:d)`
+    NegatedGroup,
+    /// `AND` inside an `OR`, e.g. `(a:b AND c:d) OR e:f`
+    AndInsideOr,
+    /// `OR` group mixing facet, tag and numeric filters, e.g. `a:b OR price > 10`
+    MixedOrGroup,
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FilterError::Syntax { position, message } => {
+                write!(f, "syntax error at position {}: {}", position, message)
+            }
+            FilterError::NegatedGroup => f.write_str("NOT can only negate a single filter"),
+            FilterError::AndInsideOr => f.write_str("AND can't be used inside an OR group"),
+            FilterError::MixedOrGroup => {
+                f.write_str("an OR group can't mix facet, tag and numeric filters")
+            }
+        }
+    }
+}
+
+impl error::Error for FilterError {}
+
+/// Parse a `filters` string. This only checks the syntax, use
+/// [Filter::validate](enum.Filter.html#method.validate) or [validate](fn.validate.html)
+/// to check the engine restrictions too.
+pub fn parse(input: &str) -> Result<Filter, FilterError> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        end: input.len(),
+    };
+    let filter = parser.parse_or()?;
+    match parser.peek() {
+        None => Ok(filter),
+        Some(token) => Err(token.unexpected("AND, OR or end of input")),
+    }
+}
+
+/// Parse and validate a `filters` string.
+pub fn validate(input: &str) -> Result<Filter, FilterError> {
+    let filter = parse(input)?;
+    filter.validate()?;
+    Ok(filter)
+}
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '(' | ')' | ':' | '"' | '<' | '>' | '=' | '!' | '\\')
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+    Word(String),
+    Quoted(String),
+    Colon,
+    LParen,
+    RParen,
+    Operator(Operator),
+    Score(u32),
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: TokenKind,
+    position: usize,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match &self.kind {
+            TokenKind::Word(word) => format!("`{}`", word),
+            TokenKind::Quoted(value) => format!("{}", Quoted(value)),
+            TokenKind::Colon => "`:`".to_string(),
+            TokenKind::LParen => "`(`".to_string(),
+            TokenKind::RParen => "`)`".to_string(),
+            TokenKind::Operator(operator) => format!("`{}`", operator),
+            TokenKind::Score(score) => format!("`<score={}>`", score),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> FilterError {
+        syntax_error(
+            self.position,
+            format!("unexpected {}, expected {}", self.describe(), expected),
+        )
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.kind, TokenKind::Word(word) if word == keyword)
+    }
+}
+
+fn syntax_error(position: usize, message: String) -> FilterError {
+    FilterError::Syntax { position, message }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, FilterError> {
+    let mut tokens = vec![];
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(position, c)) = chars.peek() {
+        let kind = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => {
+                chars.next();
+                TokenKind::LParen
+            }
+            ')' => {
+                chars.next();
+                TokenKind::RParen
+            }
+            ':' => {
+                chars.next();
+                TokenKind::Colon
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, c)) => value.push(c),
+                            None => {
+                                return Err(syntax_error(
+                                    position,
+                                    "unterminated string".to_string(),
+                                ))
+                            }
+                        },
+                        Some((_, c)) => value.push(c),
+                        None => {
+                            return Err(syntax_error(position, "unterminated string".to_string()))
+                        }
+                    }
+                }
+                TokenKind::Quoted(value)
+            }
+            '<' if input[position..].starts_with("<score=") => {
+                let rest = &input[position + "<score=".len()..];
+                let end = rest
+                    .find('>')
+                    .ok_or_else(|| syntax_error(position, "unterminated score".to_string()))?;
+                let score = rest[..end].trim().parse().map_err(|_| {
+                    syntax_error(position, format!("invalid score `{}`", &rest[..end]))
+                })?;
+                let len = "<score=".len() + end + 1;
+                while chars.peek().is_some_and(|&(i, _)| i < position + len) {
+                    chars.next();
+                }
+                TokenKind::Score(score)
+            }
+            '<' | '>' | '=' | '!' => {
+                chars.next();
+                let with_eq = chars.peek().is_some_and(|&(_, c)| c == '=');
+                let operator = match (c, with_eq) {
+                    ('<', false) => Operator::Lt,
+                    ('<', true) => Operator::Lte,
+                    ('>', false) => Operator::Gt,
+                    ('>', true) => Operator::Gte,
+                    ('=', _) => Operator::Eq,
+                    ('!', true) => Operator::Neq,
+                    _ => {
+                        return Err(syntax_error(
+                            position,
+                            "unexpected `!`, expected `!=`".to_string(),
+                        ))
+                    }
+                };
+                if with_eq && c != '=' {
+                    chars.next();
+                }
+                TokenKind::Operator(operator)
+            }
+            '\\' => return Err(syntax_error(position, "unexpected `\\`".to_string())),
+            _ => {
+                let mut word = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    if !is_word_char(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                TokenKind::Word(word)
+            }
+        };
+        tokens.push(Token { kind, position });
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self, expected: &str) -> Result<Token, FilterError> {
+        match self.tokens.get(self.position) {
+            Some(token) => {
+                self.position += 1;
+                Ok(token.clone())
+            }
+            None => Err(syntax_error(
+                self.end,
+                format!("unexpected end of input, expected {}", expected),
+            )),
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek().is_some_and(|token| token.is_keyword(keyword)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Filter, FilterError> {
+        let mut filters = vec![self.parse_and()?];
+        while self.eat_keyword("OR") {
+            filters.push(self.parse_and()?);
+        }
+        Ok(Filter::or(filters))
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, FilterError> {
+        let mut filters = vec![self.parse_not()?];
+        while self.eat_keyword("AND") {
+            filters.push(self.parse_not()?);
+        }
+        Ok(Filter::and(filters))
+    }
+
+    fn parse_not(&mut self) -> Result<Filter, FilterError> {
+        if self.eat_keyword("NOT") {
+            return Ok(Filter::Not(Box::new(self.parse_not()?)));
+        }
+        let token = self.next("a filter")?;
+        match token.kind {
+            TokenKind::LParen => {
+                let filter = self.parse_or()?;
+                let token = self.next("`)`")?;
+                match token.kind {
+                    TokenKind::RParen => Ok(filter),
+                    _ => Err(token.unexpected("`)`")),
+                }
+            }
+            TokenKind::Word(ref word) if matches!(word.as_str(), "AND" | "OR" | "TO") => {
+                Err(token.unexpected("a filter"))
+            }
+            TokenKind::Word(attribute) | TokenKind::Quoted(attribute) => {
+                self.parse_condition(attribute).map(Filter::Condition)
+            }
+            _ => Err(token.unexpected("a filter")),
+        }
+    }
+
+    fn parse_condition(&mut self, attribute: String) -> Result<Condition, FilterError> {
+        let token = self.next("`:` or a comparison operator")?;
+        match token.kind {
+            TokenKind::Colon => {}
+            TokenKind::Operator(operator) => {
+                let value = self.parse_number()?;
+                return Ok(Condition::Numeric {
+                    attribute,
+                    operator,
+                    value,
+                });
+            }
+            _ => return Err(token.unexpected("`:` or a comparison operator")),
+        }
+
+        let token = self.next("a value")?;
+        let position = token.position;
+        let value = match token.kind {
+            TokenKind::Word(ref value)
+                if !matches!(value.as_str(), "AND" | "OR" | "NOT" | "TO") =>
+            {
+                value.clone()
+            }
+            TokenKind::Quoted(value) => value,
+            _ => return Err(token.unexpected("a value")),
+        };
+
+        if self.eat_keyword("TO") {
+            let lower = number(&value, position)?;
+            let upper = self.parse_number()?;
+            return Ok(Condition::Range {
+                attribute,
+                lower,
+                upper,
+            });
+        }
+
+        let score = match self.peek() {
+            Some(Token {
+                kind: TokenKind::Score(score),
+                ..
+            }) => {
+                let score = *score;
+                self.position += 1;
+                Some(score)
+            }
+            _ => None,
+        };
+        if attribute == "_tags" {
+            Ok(Condition::Tag { value, score })
+        } else {
+            Ok(Condition::Facet {
+                attribute,
+                value,
+                score,
+            })
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<f64, FilterError> {
+        let token = self.next("a number")?;
+        match token.kind {
+            TokenKind::Word(ref value) => number(value, token.position),
+            _ => Err(token.unexpected("a number")),
+        }
+    }
+}
+
+// Parse a number of a comparison or a range, the API rejects NaN and infinities.
+fn number(value: &str, position: usize) -> Result<f64, FilterError> {
+    match value.parse::<f64>() {
+        Ok(number) if number.is_finite() => Ok(number),
+        _ => Err(syntax_error(
+            position,
+            format!("invalid number `{}`", value),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod filters_tests {
+    use super::*;
+
+    fn facet(attribute: &str, value: &str) -> Filter {
+        Filter::Condition(Condition::Facet {
+            attribute: attribute.to_string(),
+            value: value.to_string(),
+            score: None,
+        })
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("category:Book").unwrap(), facet("category", "Book"));
+        assert_eq!(
+            parse(r#""brand name":"Sci \"Fi\"""#).unwrap(),
+            facet("brand name", r#"Sci "Fi""#)
+        );
+        assert_eq!(
+            parse("_tags:featured<score=3>").unwrap(),
+            Filter::Condition(Condition::Tag {
+                value: "featured".to_string(),
+                score: Some(3),
+            })
+        );
+        assert_eq!(
+            parse("price>=-10.5").unwrap(),
+            Filter::Condition(Condition::Numeric {
+                attribute: "price".to_string(),
+                operator: Operator::Gte,
+                value: -10.5,
+            })
+        );
+        assert_eq!(
+            parse("price:10 TO 20").unwrap(),
+            Filter::Condition(Condition::Range {
+                attribute: "price".to_string(),
+                lower: 10.0,
+                upper: 20.0,
+            })
+        );
+        assert_eq!(
+            parse("a:b AND c:d OR NOT e:f").unwrap(),
+            Filter::Or(vec![
+                Filter::And(vec![facet("a", "b"), facet("c", "d")]),
+                Filter::Not(Box::new(facet("e", "f"))),
+            ])
+        );
+        assert_eq!(
+            parse("a:b AND (c:d OR (e:f OR g:h))").unwrap(),
+            Filter::And(vec![
+                facet("a", "b"),
+                Filter::Or(vec![facet("c", "d"), facet("e", "f"), facet("g", "h")]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse("category:Book AND").unwrap_err(),
+            FilterError::Syntax {
+                position: 17,
+                message: "unexpected end of input, expected a filter".to_string(),
+            }
+        );
+        assert_eq!(
+            parse("(a:b OR c:d").unwrap_err().to_string(),
+            "syntax error at position 11: unexpected end of input, expected `)`"
+        );
+        assert_eq!(
+            parse("a:b c:d").unwrap_err().to_string(),
+            "syntax error at position 4: unexpected `c`, expected AND, OR or end of input"
+        );
+        assert_eq!(
+            parse(r#"a:"b"#).unwrap_err().to_string(),
+            "syntax error at position 2: unterminated string"
+        );
+        assert_eq!(
+            parse(r#"brand:"Apple\"#).unwrap_err().to_string(),
+            "syntax error at position 6: unterminated string"
+        );
+        assert_eq!(
+            parse("price > ten").unwrap_err().to_string(),
+            "syntax error at position 8: invalid number `ten`"
+        );
+        assert_eq!(
+            parse("price > NaN").unwrap_err().to_string(),
+            "syntax error at position 8: invalid number `NaN`"
+        );
+        assert_eq!(
+            parse("price < inf").unwrap_err().to_string(),
+            "syntax error at position 8: invalid number `inf`"
+        );
+        assert_eq!(
+            parse("a:5 TO 1e400").unwrap_err().to_string(),
+            "syntax error at position 7: invalid number `1e400`"
+        );
+        assert_eq!(
+            parse("a:1e400 TO 5").unwrap_err().to_string(),
+            "syntax error at position 2: invalid number `1e400`"
+        );
+        assert_eq!(
+            parse("price:1 TO").unwrap_err().to_string(),
+            "syntax error at position 10: unexpected end of input, expected a number"
+        );
+        assert_eq!(
+            parse("a:b<score=x>").unwrap_err().to_string(),
+            "syntax error at position 3: invalid score `x`"
+        );
+        assert_eq!(
+            parse("").unwrap_err().to_string(),
+            "syntax error at position 0: unexpected end of input, expected a filter"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("a:b AND (c:d OR e:f) AND NOT g:h").is_ok());
+        assert!(validate("price < 10 OR price:20 TO 30").is_ok());
+        assert_eq!(
+            validate("NOT (a:b OR c:d)").unwrap_err(),
+            FilterError::NegatedGroup
+        );
+        assert_eq!(
+            validate("(a:b AND c:d) OR e:f").unwrap_err(),
+            FilterError::AndInsideOr
+        );
+        assert_eq!(
+            validate("a:b OR _tags:c").unwrap_err(),
+            FilterError::MixedOrGroup
+        );
+        assert_eq!(
+            validate("a:b OR price > 10").unwrap_err(),
+            FilterError::MixedOrGroup
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        for input in &[
+            "category:Book",
+            r#""brand name":"Sci \"Fi\"" AND _tags:featured<score=3>"#,
+            "price >= -10.5 AND (price:10 TO 20 OR price = 5)",
+            "(a:b AND c:d) OR NOT e:f",
+            r#"a:"AND" AND NOT (b:c OR d:e)"#,
+        ] {
+            assert_eq!(&parse(input).unwrap().to_string(), input);
+        }
+        assert_eq!(
+            parse("  a:b   AND(c:d OR(e:f))").unwrap().to_string(),
+            "a:b AND (c:d OR e:f)"
+        );
+    }
+}
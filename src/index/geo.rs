@@ -0,0 +1,566 @@
+//! Typed [geo-search](https://www.algolia.com/doc/guides/managing-results/refine-results/geolocation/) parameters.
+//!
+//! ```
+//! # #[macro_use] extern crate serde_derive;
+//! use algoliasearch::geo::{BoundingBox, GeoLoc, LatLng, Polygon};
+//! use algoliasearch::SearchQueryBuilder;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Shop {
+//!     name: String,
+//!     #[serde(rename = "_geoloc")]
+//!     geoloc: GeoLoc,
+//! }
+//!
+//! # fn main() -> Result<(), algoliasearch::geo::GeoError> {
+//! let paris = LatLng::new(48.8566, 2.3522)?;
+//! let query = SearchQueryBuilder::default()
+//!     .around_lat_lng(paris)
+//!     .inside_bounding_box(vec![BoundingBox::new(
+//!         LatLng::new(49.0, 2.0)?,
+//!         LatLng::new(48.0, 3.0)?,
+//!     )])
+//!     .inside_polygon(vec![Polygon::new(vec![
+//!         LatLng::new(48.0, 2.0)?,
+//!         LatLng::new(49.0, 2.0)?,
+//!         LatLng::new(49.0, 3.0)?,
+//!     ])?])
+//!     .build()
+//!     .unwrap();
+//! # Ok(())
+//! # }
+//! ```
+use std::{error, fmt, str::FromStr};
+
+use serde::{
+    de::{self, Deserialize, Deserializer},
+    ser::{Serialize, SerializeSeq, Serializer},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+/// Invalid geo parameter.
+pub enum GeoError {
+    /// Latitude outside of `[-90, 90]`
+    InvalidLatitude(f64),
+    /// Longitude outside of `[-180, 180]`
+    InvalidLongitude(f64),
+    /// A polygon needs at least 3 points
+    NotEnoughPoints(usize),
+    /// String that is not a `lat,lng` pair
+    InvalidFormat(String),
+}
+
+impl fmt::Display for GeoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GeoError::InvalidLatitude(lat) => {
+                write!(f, "latitude must be between -90 and 90, got {}", lat)
+            }
+            GeoError::InvalidLongitude(lng) => {
+                write!(f, "longitude must be between -180 and 180, got {}", lng)
+            }
+            GeoError::NotEnoughPoints(count) => {
+                write!(f, "a polygon needs at least 3 points, got {}", count)
+            }
+            GeoError::InvalidFormat(value) => {
+                write!(f, r#"expected "lat,lng", got "{}""#, value)
+            }
+        }
+    }
+}
+
+impl error::Error for GeoError {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A validated latitude/longitude pair.
+/// Serialized as `"lat,lng"`, the format of [aroundLatLng](https://www.algolia.com/doc/api-reference/api-parameters/aroundLatLng/).
+pub struct LatLng {
+    lat: f64,
+    lng: f64,
+}
+
+impl LatLng {
+    /// Create a point, checking the latitude and longitude ranges.
+    pub fn new(lat: f64, lng: f64) -> Result<LatLng, GeoError> {
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(GeoError::InvalidLatitude(lat));
+        }
+        if !(-180.0..=180.0).contains(&lng) {
+            return Err(GeoError::InvalidLongitude(lng));
+        }
+        Ok(LatLng { lat, lng })
+    }
+    /// Latitude
+    pub fn lat(&self) -> f64 {
+        self.lat
+    }
+    /// Longitude
+    pub fn lng(&self) -> f64 {
+        self.lng
+    }
+}
+
+impl fmt::Display for LatLng {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.lat, self.lng)
+    }
+}
+
+impl FromStr for LatLng {
+    type Err = GeoError;
+
+    fn from_str(value: &str) -> Result<LatLng, GeoError> {
+        let invalid = || GeoError::InvalidFormat(value.to_string());
+        let mut parts = value.splitn(2, ',');
+        let lat = parts.next().ok_or_else(invalid)?.trim();
+        let lng = parts.next().ok_or_else(invalid)?.trim();
+        LatLng::new(
+            lat.parse().map_err(|_| invalid())?,
+            lng.parse().map_err(|_| invalid())?,
+        )
+    }
+}
+
+impl Serialize for LatLng {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LatLng {
+    fn deserialize<D>(deserializer: D) -> Result<LatLng, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+fn lat_lng_from_coordinates<E: de::Error>(coordinates: &[f64]) -> Result<Vec<LatLng>, E> {
+    if coordinates.len() % 2 != 0 {
+        return Err(E::custom("expected an even number of coordinates"));
+    }
+    coordinates
+        .chunks(2)
+        .map(|pair| LatLng::new(pair[0], pair[1]).map_err(E::custom))
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A rectangle defined by two opposite corners,
+/// see [insideBoundingBox](https://www.algolia.com/doc/api-reference/api-parameters/insideBoundingBox/).
+pub struct BoundingBox {
+    /// First corner
+    pub p1: LatLng,
+    /// Opposite corner
+    pub p2: LatLng,
+}
+
+impl BoundingBox {
+    /// Create a bounding box from two opposite corners.
+    pub fn new(p1: LatLng, p2: LatLng) -> BoundingBox {
+        BoundingBox { p1, p2 }
+    }
+}
+
+impl Serialize for BoundingBox {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        [self.p1.lat, self.p1.lng, self.p2.lat, self.p2.lng].serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BoundingBox {
+    fn deserialize<D>(deserializer: D) -> Result<BoundingBox, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let coordinates = Vec::<f64>::deserialize(deserializer)?;
+        if coordinates.len() != 4 {
+            return Err(de::Error::invalid_length(
+                coordinates.len(),
+                &"4 coordinates",
+            ));
+        }
+        let points = lat_lng_from_coordinates(&coordinates)?;
+        Ok(BoundingBox::new(points[0], points[1]))
+    }
+}
+
+// Either a flat list of coordinates, or a list of nested values, e.g. of boxes or polygons.
+enum Items<T> {
+    Coordinates(Vec<f64>),
+    Nested(Vec<T>),
+}
+
+// Item of a list of coordinates or of nested values.
+enum Item<T> {
+    Coordinate(f64),
+    Nested(T),
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Item<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Item<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ItemVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> de::Visitor<'de> for ItemVisitor<T> {
+            type Value = Item<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a coordinate or a list of coordinates")
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Item<T>, E> {
+                Ok(Item::Coordinate(value))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Item<T>, E> {
+                Ok(Item::Coordinate(value as f64))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Item<T>, E> {
+                Ok(Item::Coordinate(value as f64))
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Item<T>, A::Error> {
+                T::deserialize(de::value::SeqAccessDeserializer::new(seq)).map(Item::Nested)
+            }
+        }
+
+        deserializer.deserialize_any(ItemVisitor(std::marker::PhantomData))
+    }
+}
+
+fn deserialize_items<'de, D, T>(deserializer: D) -> Result<Option<Items<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let items = match Option::<Vec<Item<T>>>::deserialize(deserializer)? {
+        Some(items) => items,
+        None => return Ok(None),
+    };
+    let mut coordinates = vec![];
+    let mut nested = vec![];
+    for item in items {
+        match item {
+            Item::Coordinate(coordinate) => coordinates.push(coordinate),
+            Item::Nested(value) => nested.push(value),
+        }
+    }
+    match (coordinates.is_empty(), nested.is_empty()) {
+        (false, false) => Err(de::Error::custom(
+            "expected coordinates or lists of coordinates, not both",
+        )),
+        (false, true) => Ok(Some(Items::Coordinates(coordinates))),
+        _ => Ok(Some(Items::Nested(nested))),
+    }
+}
+
+// Boxes of `insideBoundingBox`, as a list of 4 coordinates lists, or as a flat list
+// of coordinates, 4 per box, e.g. `insideBoundingBox=47.3,8.4,47.1,8.6` in a `params` string.
+pub(crate) fn deserialize_bounding_boxes<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<BoundingBox>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match deserialize_items(deserializer)? {
+        Some(Items::Coordinates(coordinates)) => {
+            if coordinates.len() % 4 != 0 {
+                return Err(de::Error::custom("expected 4 coordinates per box"));
+            }
+            let points = lat_lng_from_coordinates(&coordinates)?;
+            Ok(Some(
+                points
+                    .chunks(2)
+                    .map(|corners| BoundingBox::new(corners[0], corners[1]))
+                    .collect(),
+            ))
+        }
+        Some(Items::Nested(boxes)) => Ok(Some(boxes)),
+        None => Ok(None),
+    }
+}
+
+// Polygons of `insidePolygon`, as a list of coordinates lists, or as a flat list
+// of coordinates for a single polygon, e.g. `insidePolygon=48,2,49,2,49,3`.
+pub(crate) fn deserialize_polygons<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<Polygon>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match deserialize_items(deserializer)? {
+        Some(Items::Coordinates(coordinates)) => {
+            let points = lat_lng_from_coordinates(&coordinates)?;
+            Ok(Some(vec![Polygon::new(points).map_err(de::Error::custom)?]))
+        }
+        Some(Items::Nested(polygons)) => Ok(Some(polygons)),
+        None => Ok(None),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A polygon of at least 3 points,
+/// see [insidePolygon](https://www.algolia.com/doc/api-reference/api-parameters/insidePolygon/).
+pub struct Polygon(Vec<LatLng>);
+
+impl Polygon {
+    /// Create a polygon, checking it has at least 3 points.
+    pub fn new(points: Vec<LatLng>) -> Result<Polygon, GeoError> {
+        if points.len() < 3 {
+            return Err(GeoError::NotEnoughPoints(points.len()));
+        }
+        Ok(Polygon(points))
+    }
+    /// Points of the polygon
+    pub fn points(&self) -> &[LatLng] {
+        &self.0
+    }
+}
+
+impl Serialize for Polygon {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.len() * 2))?;
+        for point in &self.0 {
+            seq.serialize_element(&point.lat)?;
+            seq.serialize_element(&point.lng)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Polygon {
+    fn deserialize<D>(deserializer: D) -> Result<Polygon, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let coordinates = Vec::<f64>::deserialize(deserializer)?;
+        Polygon::new(lat_lng_from_coordinates(&coordinates)?).map_err(de::Error::custom)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// One step of a ranged [AroundPrecision](enum.AroundPrecision.html).
+pub struct AroundPrecisionRange {
+    /// Distance (in meters) from which this precision applies
+    pub from: u64,
+    /// Precision (in meters)
+    pub value: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+/// [https://www.algolia.com/doc/api-reference/api-parameters/aroundPrecision/](https://www.algolia.com/doc/api-reference/api-parameters/aroundPrecision/)
+pub enum AroundPrecision {
+    /// A single precision (in meters)
+    Precision(u64),
+    /// Precisions depending on the distance
+    Ranges(Vec<AroundPrecisionRange>),
+}
+
+impl From<u64> for AroundPrecision {
+    fn from(precision: u64) -> AroundPrecision {
+        AroundPrecision::Precision(precision)
+    }
+}
+
+impl From<Vec<AroundPrecisionRange>> for AroundPrecision {
+    fn from(ranges: Vec<AroundPrecisionRange>) -> AroundPrecision {
+        AroundPrecision::Ranges(ranges)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct GeoPoint {
+    lat: f64,
+    lng: f64,
+}
+
+impl<'a> From<&'a LatLng> for GeoPoint {
+    fn from(point: &'a LatLng) -> GeoPoint {
+        GeoPoint {
+            lat: point.lat,
+            lng: point.lng,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GeoPoints {
+    Point(GeoPoint),
+    Points(Vec<GeoPoint>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// The [_geoloc](https://www.algolia.com/doc/guides/managing-results/refine-results/geolocation/#enabling-geo-search-by-adding-geolocation-data-to-records)
+/// attribute of a record, serialized as `{"lat": .., "lng": ..}` or a list of those.
+pub enum GeoLoc {
+    #[allow(missing_docs)]
+    Point(LatLng),
+    #[allow(missing_docs)]
+    Points(Vec<LatLng>),
+}
+
+impl Serialize for GeoLoc {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            GeoLoc::Point(point) => GeoPoint::from(point).serialize(serializer),
+            GeoLoc::Points(points) => serializer.collect_seq(points.iter().map(GeoPoint::from)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for GeoLoc {
+    fn deserialize<D>(deserializer: D) -> Result<GeoLoc, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match GeoPoints::deserialize(deserializer)? {
+            GeoPoints::Point(point) => LatLng::new(point.lat, point.lng)
+                .map(GeoLoc::Point)
+                .map_err(de::Error::custom),
+            GeoPoints::Points(points) => points
+                .into_iter()
+                .map(|point| LatLng::new(point.lat, point.lng))
+                .collect::<Result<_, _>>()
+                .map(GeoLoc::Points)
+                .map_err(de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod geo_tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn test_lat_lng() {
+        assert_eq!(LatLng::new(91.0, 0.0), Err(GeoError::InvalidLatitude(91.0)));
+        assert_eq!(
+            LatLng::new(0.0, -180.5),
+            Err(GeoError::InvalidLongitude(-180.5))
+        );
+        assert_eq!(
+            "40.71, -74.01".parse::<LatLng>().unwrap(),
+            LatLng::new(40.71, -74.01).unwrap()
+        );
+        assert_eq!(
+            "40.71".parse::<LatLng>(),
+            Err(GeoError::InvalidFormat("40.71".to_string()))
+        );
+        assert_eq!(
+            serde_json::to_string(&LatLng::new(40.71, -74.01).unwrap()).unwrap(),
+            r#""40.71,-74.01""#
+        );
+        assert_eq!(
+            serde_json::from_str::<LatLng>(r#""95,0""#)
+                .unwrap_err()
+                .to_string(),
+            "latitude must be between -90 and 90, got 95"
+        );
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let bounding_box = BoundingBox::new(
+            LatLng::new(49.0, 2.0).unwrap(),
+            LatLng::new(48.0, 3.5).unwrap(),
+        );
+        assert_eq!(
+            serde_json::to_string(&vec![bounding_box]).unwrap(),
+            "[[49.0,2.0,48.0,3.5]]"
+        );
+        assert_eq!(
+            serde_json::from_str::<BoundingBox>("[49.0,2.0,48.0,3.5]").unwrap(),
+            bounding_box
+        );
+        assert!(serde_json::from_str::<BoundingBox>("[49.0,2.0,48.0]").is_err());
+    }
+
+    #[test]
+    fn test_polygon() {
+        let points = vec![
+            LatLng::new(48.0, 2.0).unwrap(),
+            LatLng::new(49.0, 2.0).unwrap(),
+        ];
+        assert_eq!(
+            Polygon::new(points.clone()),
+            Err(GeoError::NotEnoughPoints(2))
+        );
+        let mut points = points;
+        points.push(LatLng::new(49.0, 3.0).unwrap());
+        let polygon = Polygon::new(points).unwrap();
+        assert_eq!(
+            serde_json::to_string(&polygon).unwrap(),
+            "[48.0,2.0,49.0,2.0,49.0,3.0]"
+        );
+        assert_eq!(
+            serde_json::from_str::<Polygon>("[48.0,2.0,49.0,2.0,49.0,3.0]").unwrap(),
+            polygon
+        );
+        assert!(serde_json::from_str::<Polygon>("[48.0,2.0,49.0,2.0]").is_err());
+    }
+
+    #[test]
+    fn test_around_precision() {
+        assert_eq!(
+            serde_json::to_string(&AroundPrecision::Precision(100)).unwrap(),
+            "100"
+        );
+        let ranges = AroundPrecision::Ranges(vec![
+            AroundPrecisionRange { from: 0, value: 25 },
+            AroundPrecisionRange {
+                from: 2000,
+                value: 1000,
+            },
+        ]);
+        let json = r#"[{"from":0,"value":25},{"from":2000,"value":1000}]"#;
+        assert_eq!(serde_json::to_string(&ranges).unwrap(), json);
+        assert_eq!(
+            serde_json::from_str::<AroundPrecision>(json).unwrap(),
+            ranges
+        );
+    }
+
+    #[test]
+    fn test_geoloc() {
+        let point = LatLng::new(48.5, 2.25).unwrap();
+        assert_eq!(
+            serde_json::to_string(&GeoLoc::Point(point)).unwrap(),
+            r#"{"lat":48.5,"lng":2.25}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&GeoLoc::Points(vec![point, point])).unwrap(),
+            r#"[{"lat":48.5,"lng":2.25},{"lat":48.5,"lng":2.25}]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<GeoLoc>(r#"{"lat":48.5,"lng":2.25}"#).unwrap(),
+            GeoLoc::Point(point)
+        );
+        assert_eq!(
+            serde_json::from_str::<GeoLoc>(r#"[{"lat":48.5,"lng":2.25}]"#).unwrap(),
+            GeoLoc::Points(vec![point])
+        );
+        assert!(serde_json::from_str::<GeoLoc>(r#"{"lat":100,"lng":2.25}"#).is_err());
+    }
+}
@@ -1,17 +1,27 @@
-use std::{fmt, marker::PhantomData};
+use std::{fmt, marker::PhantomData, sync::Arc};
 
 use chrono::{DateTime, Utc};
 
-use reqwest::{header::HeaderMap, Client};
+use reqwest::header::{HeaderMap, HeaderValue};
 use serde::{
     de::{self, Deserialize, DeserializeOwned, Deserializer, Visitor},
     ser::{Serialize, Serializer},
 };
 
-use crate::error::Error;
+use crate::{
+    error::Error,
+    index::cache::{CacheKey, SearchCache},
+    request::{auth_headers, CallType, RequestOptions, TransportExt},
+    transport::Transport,
+};
 
+pub mod cache;
+pub mod filters;
+pub mod geo;
+pub(crate) mod params;
 pub mod settings;
 pub mod task;
+pub mod url_state;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -36,6 +46,12 @@ pub struct SearchResult<T> {
     pub query: String,
     /// Params
     pub params: String,
+    #[serde(rename = "abTestID")]
+    /// A/B test the search was part of
+    pub ab_test_id: Option<u64>,
+    #[serde(rename = "abTestVariantID")]
+    /// Variant of the A/B test the search was served by, starting at 1
+    pub ab_test_variant_id: Option<u64>,
 }
 
 #[derive(Clone, Debug, PartialEq, Hash)]
@@ -130,13 +146,14 @@ mod ignore_plurals_tests {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
 pub enum StringOrVecOfString {
     String(String),
     VecOfString(Vec<String>),
 }
 
-#[derive(Debug, Serialize, Default, Builder)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default, Builder)]
 #[serde(rename_all = "camelCase")]
 #[builder(default)]
 /// algolia search parameters
@@ -273,7 +290,7 @@ pub struct SearchQuery {
     #[builder(setter(into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// [https://www.algolia.com/doc/api-reference/api-parameters/aroundLatLng/](https://www.algolia.com/doc/api-reference/api-parameters/aroundLatLng/)
-    around_lat_lng: Option<String>,
+    around_lat_lng: Option<geo::LatLng>,
     #[builder(setter(into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "aroundLatLngViaIP")]
@@ -286,19 +303,27 @@ pub struct SearchQuery {
     #[builder(setter(into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// [https://www.algolia.com/doc/api-reference/api-parameters/aroundPrecision/](https://www.algolia.com/doc/api-reference/api-parameters/aroundPrecision/)
-    around_precision: Option<u64>,
+    around_precision: Option<geo::AroundPrecision>,
     #[builder(setter(into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// [https://www.algolia.com/doc/api-reference/api-parameters/minimumAroundRadius/](https://www.algolia.com/doc/api-reference/api-parameters/minimumAroundRadius/)
     minimum_around_radius: Option<u64>,
     #[builder(setter(into))]
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "geo::deserialize_bounding_boxes"
+    )]
     /// [https://www.algolia.com/doc/api-reference/api-parameters/insideBoundingBox/](https://www.algolia.com/doc/api-reference/api-parameters/insideBoundingBox/)
-    inside_bounding_box: Option<Vec<f64>>,
+    inside_bounding_box: Option<Vec<geo::BoundingBox>>,
     #[builder(setter(into))]
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "geo::deserialize_polygons"
+    )]
     /// [https://www.algolia.com/doc/api-reference/api-parameters/insidePolygon/](https://www.algolia.com/doc/api-reference/api-parameters/insidePolygon/)
-    inside_polygon: Option<Vec<f64>>,
+    inside_polygon: Option<Vec<geo::Polygon>>,
 
     // languages
     #[builder(setter(into))]
@@ -411,6 +436,41 @@ pub struct SearchQuery {
     percentile_computation: Option<u64>,
 }
 
+impl SearchQuery {
+    /// Parse a `params` string, as found in [SearchResult::params](struct.SearchResult.html#structfield.params).
+    /// ```
+    /// # use algoliasearch::index::SearchQuery;
+    /// let query = SearchQuery::from_params("query=phone&hitsPerPage=10&facets=%5B%22brand%22%5D").unwrap();
+    /// assert_eq!(query.to_params(), "facets=%5B%22brand%22%5D&hitsPerPage=10&query=phone");
+    /// ```
+    pub fn from_params(params: &str) -> Result<SearchQuery, Error> {
+        params::from_params(params).map_err(|e| Error::Params(e.to_string()))
+    }
+    /// Encode the query as a `params` string.
+    pub fn to_params(&self) -> String {
+        params::to_params(self)
+    }
+    /// Layer `overrides` on top of this query: every parameter set in `overrides`
+    /// replaces the one of `self`.
+    /// ```
+    /// # use algoliasearch::SearchQueryBuilder;
+    /// let defaults = SearchQueryBuilder::default().hits_per_page(20).analytics(false).build().unwrap();
+    /// let input = SearchQueryBuilder::default().query("phone".to_string()).hits_per_page(5).build().unwrap();
+    /// let query = defaults.merge(&input).unwrap();
+    /// assert_eq!(query.to_params(), "analytics=false&hitsPerPage=5&query=phone");
+    /// ```
+    pub fn merge(&self, overrides: &SearchQuery) -> Result<SearchQuery, Error> {
+        let mut merged = serde_json::to_value(self)?;
+        let overrides = serde_json::to_value(overrides)?;
+        if let (Some(merged), serde_json::Value::Object(overrides)) =
+            (merged.as_object_mut(), overrides)
+        {
+            merged.extend(overrides.into_iter().filter(|(_, value)| !value.is_null()));
+        }
+        Ok(serde_json::from_value(merged)?)
+    }
+}
+
 #[cfg(test)]
 mod query_builder {
     use super::*;
@@ -429,6 +489,124 @@ mod query_builder {
             r#"{"query":null,"page":1,"hitsPerPage":6}"#
         );
     }
+
+    #[test]
+    fn test_params() {
+        let query = SearchQueryBuilder::default()
+            .query("hello world".to_string())
+            .attributes_to_retrieve(vec!["name".to_string(), "age".to_string()])
+            .facet_filters(vec![
+                StringOrVecOfString::String("brand:apple".to_string()),
+                StringOrVecOfString::VecOfString(vec!["a:b".to_string(), "c:d".to_string()]),
+            ])
+            .typo_tolerance(settings::TypoTolerance::Strict)
+            .around_radius(AroundRadius::All)
+            .around_lat_lng(geo::LatLng::new(48.5, 2.25).unwrap())
+            .analytics(false)
+            .page(2)
+            .build()
+            .unwrap();
+        let params = query.to_params();
+        assert_eq!(
+            params,
+            "analytics=false&aroundLatLng=48.5%2C2.25&aroundRadius=all&\
+             attributesToRetrieve=%5B%22name%22%2C%22age%22%5D&\
+             facetFilters=%5B%22brand%3Aapple%22%2C%5B%22a%3Ab%22%2C%22c%3Ad%22%5D%5D&\
+             page=2&query=hello+world&typoTolerance=strict"
+        );
+        assert_eq!(SearchQuery::from_params(&params).unwrap(), query);
+    }
+
+    #[test]
+    fn test_from_params() {
+        let query = SearchQuery::from_params(
+            "query=123&attributesToRetrieve=name,age&typoTolerance=true&aroundRadius=20&unknown=1",
+        )
+        .unwrap();
+        assert_eq!(
+            query,
+            SearchQueryBuilder::default()
+                .query("123".to_string())
+                .attributes_to_retrieve(vec!["name".to_string(), "age".to_string()])
+                .typo_tolerance(settings::TypoTolerance::Enabled)
+                .around_radius(AroundRadius::Radius(20))
+                .build()
+                .unwrap()
+        );
+        assert!(SearchQuery::from_params("hitsPerPage=many").is_err());
+        let bounding_box = geo::BoundingBox::new(
+            geo::LatLng::new(47.3, 8.4).unwrap(),
+            geo::LatLng::new(47.1, 8.6).unwrap(),
+        );
+        for params in [
+            "insideBoundingBox=47.3,8.4,47.1,8.6",
+            "insideBoundingBox=%5B%5B47.3%2C8.4%2C47.1%2C8.6%5D%5D",
+        ] {
+            assert_eq!(
+                SearchQuery::from_params(params).unwrap(),
+                SearchQueryBuilder::default()
+                    .inside_bounding_box(vec![bounding_box])
+                    .build()
+                    .unwrap()
+            );
+        }
+        assert!(SearchQuery::from_params("insideBoundingBox=47.3,8.4,47.1").is_err());
+        let polygon = geo::Polygon::new(vec![
+            geo::LatLng::new(48.0, 2.0).unwrap(),
+            geo::LatLng::new(49.0, 2.0).unwrap(),
+            geo::LatLng::new(49.0, 3.0).unwrap(),
+        ])
+        .unwrap();
+        for params in [
+            "insidePolygon=48,2,49,2,49,3",
+            "insidePolygon=%5B48,2,49,2,49,3%5D",
+            "insidePolygon=%5B%5B48,2,49,2,49,3%5D%5D",
+        ] {
+            assert_eq!(
+                SearchQuery::from_params(params).unwrap(),
+                SearchQueryBuilder::default()
+                    .inside_polygon(vec![polygon.clone()])
+                    .build()
+                    .unwrap()
+            );
+        }
+        assert_eq!(
+            SearchQuery::from_params(
+                "insidePolygon=%5B%5B48,2,49,2,49,3%5D,%5B48,2,49,2,49,3%5D%5D"
+            )
+            .unwrap(),
+            SearchQueryBuilder::default()
+                .inside_polygon(vec![polygon.clone(), polygon])
+                .build()
+                .unwrap()
+        );
+        assert!(SearchQuery::from_params("insidePolygon=48,2,49,2").is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let tenant = SearchQueryBuilder::default()
+            .analytics_tags(vec!["tenant".to_string()])
+            .hits_per_page(20)
+            .build()
+            .unwrap();
+        let page = SearchQueryBuilder::default()
+            .hits_per_page(10)
+            .facets(vec!["brand".to_string()])
+            .build()
+            .unwrap();
+        let input = SearchQuery::from("phone");
+        assert_eq!(
+            tenant.merge(&page).unwrap().merge(&input).unwrap(),
+            SearchQueryBuilder::default()
+                .query("phone".to_string())
+                .analytics_tags(vec!["tenant".to_string()])
+                .hits_per_page(10)
+                .facets(vec!["brand".to_string()])
+                .build()
+                .unwrap()
+        );
+    }
 }
 
 #[derive(Serialize)]
@@ -497,11 +675,55 @@ pub struct Index<T> {
     pub application_id: String,
     /// Index name
     pub index_name: String,
-    pub(crate) api_key: String,
+    // None when the key comes from the credentials provider of the client
+    pub(crate) api_key: Option<String>,
     pub(crate) base_url: String,
+    pub(crate) user_id: Option<HeaderValue>,
+    pub(crate) cache: Option<SearchCache>,
+    pub(crate) transport: Arc<dyn Transport>,
     pub(crate) index_type: PhantomData<T>,
 }
 
+impl<T> Index<T> {
+    /// Copy of the index sending its requests on behalf of a user ID, so they are
+    /// routed to the cluster of the user on multi-cluster applications.
+    /// Fails if the user ID can't be sent in a header, e.g. when it holds a newline.
+    pub fn with_user_id(&self, user_id: &str) -> Result<Index<T>, Error> {
+        let user_id = HeaderValue::from_str(user_id)
+            .map_err(|_| Error::InvalidHeader(crate::USER_ID_HEADER.to_string()))?;
+        Ok(Index {
+            application_id: self.application_id.clone(),
+            index_name: self.index_name.clone(),
+            api_key: self.api_key.clone(),
+            base_url: self.base_url.clone(),
+            user_id: Some(user_id),
+            cache: self.cache.clone(),
+            transport: self.transport.clone(),
+            index_type: PhantomData,
+        })
+    }
+    /// Copy of the index caching its searches, see [SearchCache](cache/struct.SearchCache.html).
+    /// The cached searches of the index are dropped after each of its writes.
+    pub fn with_cache(&self, cache: &SearchCache) -> Index<T> {
+        Index {
+            application_id: self.application_id.clone(),
+            index_name: self.index_name.clone(),
+            api_key: self.api_key.clone(),
+            base_url: self.base_url.clone(),
+            user_id: self.user_id.clone(),
+            cache: Some(cache.clone()),
+            transport: self.transport.clone(),
+            index_type: PhantomData,
+        }
+    }
+    // Drop the cached searches of the index, after a write.
+    fn invalidate_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(&self.index_name);
+        }
+    }
+}
+
 impl<T: DeserializeOwned + Serialize> Index<T> {
     /// Search the index.
     /// This method accepts a [&str](https://doc.rust-lang.org/std/str/index.html):
@@ -541,19 +763,36 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
     /// # }
     /// ```
     pub async fn search(&self, query: impl Into<SearchQuery>) -> Result<SearchResult<T>, Error> {
+        self.search_with_options(query, &RequestOptions::default())
+            .await
+    }
+    /// Same as [search](#method.search), with [RequestOptions](../struct.RequestOptions.html).
+    pub async fn search_with_options(
+        &self,
+        query: impl Into<SearchQuery>,
+        options: &RequestOptions,
+    ) -> Result<SearchResult<T>, Error> {
         let query = query.into();
         let uri = format!("{}/indexes/{}/query", self.base_url, self.index_name);
-        let params = serde_urlencoded::to_string(query).expect("failed to encode params");
-        let params = &SearchQueryBody { params };
-        Client::new()
+        let params = &SearchQueryBody {
+            params: query.to_params(),
+        };
+        let request = self
+            .transport
             .post(&uri)
             .headers(self.get_headers())
+            .with_options(options, CallType::Read)
+            .operation("search", &self.index_name)
             .json(&params)
-            .send()
-            .await?
-            .json::<SearchResult<T>>()
-            .await
-            .map_err(|e| e.into())
+            .send();
+        let body = match &self.cache {
+            Some(cache) => {
+                let key = CacheKey::new(self, params.params.clone(), options);
+                cache.get_or_fetch(key, request).await?
+            }
+            None => Arc::new(request.await?),
+        };
+        serde_json::from_slice(&body).map_err(|e| e.into())
     }
     /// Get an object from the index.
     /// ```no_run
@@ -573,23 +812,36 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
         &self,
         object_id: &str,
         attributes_to_retrieve: Option<&[&str]>,
+    ) -> Result<T, Error> {
+        self.get_object_with_options(
+            object_id,
+            attributes_to_retrieve,
+            &RequestOptions::default(),
+        )
+        .await
+    }
+    /// Same as [get_object](#method.get_object), with [RequestOptions](../struct.RequestOptions.html).
+    pub async fn get_object_with_options(
+        &self,
+        object_id: &str,
+        attributes_to_retrieve: Option<&[&str]>,
+        options: &RequestOptions,
     ) -> Result<T, Error> {
         let uri = format!(
             "{}/indexes/{}/{}",
             self.base_url, self.index_name, object_id
         );
-        Client::new()
+        self.transport
             .get(&uri)
             .headers(self.get_headers())
+            .with_options(options, CallType::Read)
+            .operation("get_object", &self.index_name)
             .query(&[(
                 "attributes_to_retrieve",
                 attributes_to_retrieve.map(|el| el.join(",")),
             )])
-            .send()
-            .await?
-            .json()
+            .send_json()
             .await
-            .map_err(|e| e.into())
     }
     /// Add an object to the index.
     /// ```no_run
@@ -606,16 +858,27 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
     /// # }
     /// ```
     pub async fn add_object(&self, object: &T) -> Result<AddObjectResult, Error> {
+        self.add_object_with_options(object, &RequestOptions::default())
+            .await
+    }
+    /// Same as [add_object](#method.add_object), with [RequestOptions](../struct.RequestOptions.html).
+    pub async fn add_object_with_options(
+        &self,
+        object: &T,
+        options: &RequestOptions,
+    ) -> Result<AddObjectResult, Error> {
         let uri = format!("{}/indexes/{}", self.base_url, self.index_name);
-        Client::new()
+        let result = self
+            .transport
             .post(&uri)
             .headers(self.get_headers())
+            .with_options(options, CallType::Write)
+            .operation("add_object", &self.index_name)
             .json(&object)
-            .send()
-            .await?
-            .json()
-            .await
-            .map_err(|e| e.into())
+            .send_json()
+            .await;
+        self.invalidate_cache();
+        result
     }
     /// Add several objects to the index.
     /// ```no_run
@@ -633,6 +896,15 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
     /// # }
     /// ```
     pub async fn add_objects(&self, objects: &[&T]) -> Result<BatchedOperatioResult, Error> {
+        self.add_objects_with_options(objects, &RequestOptions::default())
+            .await
+    }
+    /// Same as [add_objects](#method.add_objects), with [RequestOptions](../struct.RequestOptions.html).
+    pub async fn add_objects_with_options(
+        &self,
+        objects: &[&T],
+        options: &RequestOptions,
+    ) -> Result<BatchedOperatioResult, Error> {
         let uri = format!("{}/indexes/{}/batch", self.base_url, self.index_name);
         let requests = objects.iter().fold(vec![], |mut acc, x| {
             acc.push(BatchedOperationItem {
@@ -642,15 +914,17 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
             acc
         });
         let requests = BatchedOperation { requests };
-        Client::new()
+        let result = self
+            .transport
             .post(&uri)
             .headers(self.get_headers())
+            .with_options(options, CallType::Write)
+            .operation("add_objects", &self.index_name)
             .json(&requests)
-            .send()
-            .await?
-            .json()
-            .await
-            .map_err(|e| e.into())
+            .send_json()
+            .await;
+        self.invalidate_cache();
+        result
     }
     /// Add or replace an object with a given object ID.
     /// If the object does not exist, it will be created. If it already exists, it will be replaced.
@@ -671,20 +945,32 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
         &self,
         object: &T,
         object_id: &str,
+    ) -> Result<UpdateOperationResult, Error> {
+        self.update_object_with_options(object, object_id, &RequestOptions::default())
+            .await
+    }
+    /// Same as [update_object](#method.update_object), with [RequestOptions](../struct.RequestOptions.html).
+    pub async fn update_object_with_options(
+        &self,
+        object: &T,
+        object_id: &str,
+        options: &RequestOptions,
     ) -> Result<UpdateOperationResult, Error> {
         let uri = format!(
             "{}/indexes/{}/{}",
             self.base_url, self.index_name, object_id
         );
-        Client::new()
+        let result = self
+            .transport
             .put(&uri)
             .headers(self.get_headers())
+            .with_options(options, CallType::Write)
+            .operation("update_object", &self.index_name)
             .json(object)
-            .send()
-            .await?
-            .json()
-            .await
-            .map_err(|e| e.into())
+            .send_json()
+            .await;
+        self.invalidate_cache();
+        result
     }
     /// Add or replace several objects with a given object ID.
     /// If the object does not exist, it will be created. If it already exists, it will be replaced..
@@ -703,6 +989,15 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
     /// # }
     /// ```
     pub async fn update_objects(&self, objects: &[&T]) -> Result<BatchedOperatioResult, Error> {
+        self.update_objects_with_options(objects, &RequestOptions::default())
+            .await
+    }
+    /// Same as [update_objects](#method.update_objects), with [RequestOptions](../struct.RequestOptions.html).
+    pub async fn update_objects_with_options(
+        &self,
+        objects: &[&T],
+        options: &RequestOptions,
+    ) -> Result<BatchedOperatioResult, Error> {
         let uri = format!("{}/indexes/{}/batch", self.base_url, self.index_name);
         let requests = objects.iter().fold(vec![], |mut acc, x| {
             acc.push(BatchedOperationItem {
@@ -712,15 +1007,17 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
             acc
         });
         let requests = BatchedOperation { requests };
-        Client::new()
+        let result = self
+            .transport
             .post(&uri)
             .headers(self.get_headers())
+            .with_options(options, CallType::Write)
+            .operation("update_objects", &self.index_name)
             .json(&requests)
-            .send()
-            .await?
-            .json()
-            .await
-            .map_err(|e| e.into())
+            .send_json()
+            .await;
+        self.invalidate_cache();
+        result
     }
     /// Delete an object from the index.
     /// ```no_run
@@ -737,18 +1034,29 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
     /// # }
     /// ```
     pub async fn delete_object(&self, object_id: &str) -> Result<DeleteObjectResult, Error> {
+        self.delete_object_with_options(object_id, &RequestOptions::default())
+            .await
+    }
+    /// Same as [delete_object](#method.delete_object), with [RequestOptions](../struct.RequestOptions.html).
+    pub async fn delete_object_with_options(
+        &self,
+        object_id: &str,
+        options: &RequestOptions,
+    ) -> Result<DeleteObjectResult, Error> {
         let uri = format!(
             "{}/indexes/{}/{}",
             self.base_url, self.index_name, object_id
         );
-        Client::new()
+        let result = self
+            .transport
             .delete(&uri)
             .headers(self.get_headers())
-            .send()
-            .await?
-            .json()
-            .await
-            .map_err(|e| e.into())
+            .with_options(options, CallType::Write)
+            .operation("delete_object", &self.index_name)
+            .send_json()
+            .await;
+        self.invalidate_cache();
+        result
     }
     /// Clear all objects from an index.
     /// ```no_run
@@ -764,15 +1072,25 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
     /// # }
     /// ```
     pub async fn clear_objects(&self) -> Result<UpdateOperationResult, Error> {
+        self.clear_objects_with_options(&RequestOptions::default())
+            .await
+    }
+    /// Same as [clear_objects](#method.clear_objects), with [RequestOptions](../struct.RequestOptions.html).
+    pub async fn clear_objects_with_options(
+        &self,
+        options: &RequestOptions,
+    ) -> Result<UpdateOperationResult, Error> {
         let uri = format!("{}/indexes/{}/clear", self.base_url, self.index_name);
-        Client::new()
+        let result = self
+            .transport
             .post(&uri)
             .headers(self.get_headers())
-            .send()
-            .await?
-            .json()
-            .await
-            .map_err(|e| e.into())
+            .with_options(options, CallType::Write)
+            .operation("clear_objects", &self.index_name)
+            .send_json()
+            .await;
+        self.invalidate_cache();
+        result
     }
     /// Get the index's settings.
     /// ```no_run
@@ -789,15 +1107,22 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
     /// # }
     /// ```
     pub async fn get_settings(&self) -> Result<settings::IndexSettings, Error> {
+        self.get_settings_with_options(&RequestOptions::default())
+            .await
+    }
+    /// Same as [get_settings](#method.get_settings), with [RequestOptions](../struct.RequestOptions.html).
+    pub async fn get_settings_with_options(
+        &self,
+        options: &RequestOptions,
+    ) -> Result<settings::IndexSettings, Error> {
         let uri = format!("{}/indexes/{}/settings", self.base_url, self.index_name);
-        Client::new()
+        self.transport
             .get(&uri)
             .headers(self.get_headers())
-            .send()
-            .await?
-            .json()
+            .with_options(options, CallType::Read)
+            .operation("get_settings", &self.index_name)
+            .send_json()
             .await
-            .map_err(|e| e.into())
     }
     /// Set the index's settings.
     /// ```no_run
@@ -827,27 +1152,39 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
         settings: settings::IndexSettings,
         forward_to_replicas: Option<bool>,
     ) -> Result<UpdateOperationResult, Error> {
-        let forward_to_replicas = forward_to_replicas.unwrap_or(false);
+        let mut options = RequestOptions::default();
+        if let Some(forward_to_replicas) = forward_to_replicas {
+            options = options.forward_to_replicas(forward_to_replicas);
+        }
+        self.set_settings_with_options(settings, &options).await
+    }
+    /// Same as [set_settings](#method.set_settings), with [RequestOptions](../struct.RequestOptions.html),
+    /// which also carry `forward_to_replicas`.
+    pub async fn set_settings_with_options(
+        &self,
+        settings: settings::IndexSettings,
+        options: &RequestOptions,
+    ) -> Result<UpdateOperationResult, Error> {
         let uri = format!("{}/indexes/{}/settings", self.base_url, self.index_name);
-        Client::new()
+        let result = self
+            .transport
             .put(&uri)
             .headers(self.get_headers())
+            .with_options(options, CallType::Write)
+            .forward_to_replicas(options)
+            .operation("set_settings", &self.index_name)
             .json(&settings)
-            .query(&[("forwardToReplicas", forward_to_replicas)])
-            .send()
-            .await?
-            .json()
-            .await
-            .map_err(|e| e.into())
+            .send_json()
+            .await;
+        self.invalidate_cache();
+        result
     }
     // Build authentication headers.
     fn get_headers(&self) -> HeaderMap {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            crate::APPLICATION_ID_HEADER,
-            self.application_id.parse().unwrap(),
-        );
-        headers.insert(crate::API_KEY_HEADER, self.api_key.parse().unwrap());
+        let mut headers = auth_headers(&self.application_id, self.api_key.as_deref());
+        if let Some(user_id) = &self.user_id {
+            headers.insert(crate::USER_ID_HEADER, user_id.clone());
+        }
         headers
     }
 
@@ -870,17 +1207,25 @@ impl<T: DeserializeOwned + Serialize> Index<T> {
     /// # }
     /// ```
     pub async fn get_task_status(&self, task_id: u64) -> Result<task::TaskStatus, Error> {
+        self.get_task_status_with_options(task_id, &RequestOptions::default())
+            .await
+    }
+    /// Same as [get_task_status](#method.get_task_status), with [RequestOptions](../struct.RequestOptions.html).
+    pub async fn get_task_status_with_options(
+        &self,
+        task_id: u64,
+        options: &RequestOptions,
+    ) -> Result<task::TaskStatus, Error> {
         let uri = format!(
             "{}/indexes/{}/task/{}",
             self.base_url, self.index_name, task_id
         );
-        Client::new()
+        self.transport
             .get(&uri)
             .headers(self.get_headers())
-            .send()
-            .await?
-            .json()
+            .with_options(options, CallType::Read)
+            .operation("get_task_status", &self.index_name)
+            .send_json()
             .await
-            .map_err(|e| e.into())
     }
 }
@@ -0,0 +1,150 @@
+//! Encoding of search parameters in the `params` string format:
+//! a url-encoded query string whose string values are raw and
+//! whose other values (numbers, booleans, arrays) are JSON.
+use serde::{
+    de::{
+        self,
+        value::{Error, MapDeserializer, SeqDeserializer},
+        DeserializeOwned, Deserializer, IntoDeserializer, Visitor,
+    },
+    forward_to_deserialize_any, Serialize, Serializer,
+};
+use serde_json::Value;
+
+/// Encode a value serializing to a JSON object as a `params` string.
+pub(crate) fn to_params<T: Serialize>(value: &T) -> String {
+    let value = serde_json::to_value(value).expect("failed to encode params");
+    let object = match value {
+        Value::Object(object) => object,
+        _ => panic!("params must be encoded from a struct"),
+    };
+    let pairs = object
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| match value {
+            Value::String(value) => (key, value),
+            value => (key, value.to_string()),
+        })
+        .collect::<Vec<_>>();
+    serde_urlencoded::to_string(pairs).expect("failed to encode params")
+}
+
+/// Serialize search parameters as a JSON object, leaving out unset parameters.
+pub(crate) fn serialize_object<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    let mut value = serde_json::to_value(value).map_err(serde::ser::Error::custom)?;
+    if let Some(object) = value.as_object_mut() {
+        object.retain(|_, value| !value.is_null());
+    }
+    value.serialize(serializer)
+}
+
+/// Decode a `params` string.
+pub(crate) fn from_params<T: DeserializeOwned>(params: &str) -> Result<T, Error> {
+    let pairs = serde_urlencoded::from_str::<Vec<(String, String)>>(params)
+        .map_err(|e| de::Error::custom(e.to_string()))?;
+    T::deserialize(MapDeserializer::new(
+        pairs
+            .into_iter()
+            .map(|(key, value)| (key, ParamValue(value))),
+    ))
+}
+
+// A single value of a `params` string, decoded according to the expected type.
+struct ParamValue(String);
+
+impl ParamValue {
+    fn json(&self) -> Result<Value, Error> {
+        serde_json::from_str(&self.0)
+            .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(&self.0), &"a JSON value"))
+    }
+}
+
+impl IntoDeserializer<'_, Error> for ParamValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! forward_to_json {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                self.json()?
+                    .$method(visitor)
+                    .map_err(|e| de::Error::custom(e.to_string()))
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ParamValue {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.json() {
+            Ok(Value::String(_)) | Ok(Value::Null) | Err(_) => visitor.visit_string(self.0),
+            Ok(value) => value
+                .deserialize_any(visitor)
+                .map_err(|e| de::Error::custom(e.to_string())),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.json() {
+            Ok(value @ Value::Array(_)) => value
+                .deserialize_seq(visitor)
+                .map_err(|e| de::Error::custom(e.to_string())),
+            // lists can also be written comma separated
+            _ => visitor.visit_seq(SeqDeserializer::new(
+                self.0
+                    .split(',')
+                    .map(|value| ParamValue(value.trim().to_string())),
+            )),
+        }
+    }
+
+    forward_to_json! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_map
+    }
+
+    forward_to_deserialize_any! {
+        char bytes byte_buf unit unit_struct tuple tuple_struct struct identifier ignored_any
+    }
+}
@@ -2,6 +2,8 @@ use serde_derive::Deserialize;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
+// the fields are only read through Debug
+#[allow(dead_code)]
 pub struct TaskStatus {
     status: String,
     pending_task: bool,
@@ -0,0 +1,345 @@
+//! Mapping between a [SearchQuery](../struct.SearchQuery.html) and the query string
+//! of a browser URL, to share deep links between server-side rendering and
+//! an InstantSearch front-end.
+//!
+//! ```
+//! use algoliasearch::{
+//!     url_state::{AttributeMapping, UrlStateCodecBuilder},
+//!     SearchQueryBuilder,
+//! };
+//!
+//! let codec = UrlStateCodecBuilder::default()
+//!     .query_key("q")
+//!     .default_index(Some("products".to_string()))
+//!     .attributes(vec![
+//!         AttributeMapping::disjunctive("brand"),
+//!         AttributeMapping::range("price").key("p"),
+//!     ])
+//!     .build()
+//!     .unwrap();
+//!
+//! let state = codec.decode("q=phone&page=2&brand=Apple&brand=Samsung&p=10:200&sortBy=products_price_asc");
+//! assert_eq!(state.index_name.as_deref(), Some("products_price_asc"));
+//! assert_eq!(
+//!     codec.encode(state.index_name.as_deref(), &state.query),
+//!     "q=phone&page=2&sortBy=products_price_asc&brand=Apple&brand=Samsung&p=10%3A200"
+//! );
+//! ```
+use super::{SearchQuery, StringOrVecOfString};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// How an attribute is refined.
+pub enum AttributeKind {
+    /// Values are combined with `OR`, like a refinement list
+    Disjunctive,
+    /// Values are combined with `AND`
+    Conjunctive,
+    /// A numeric `min:max` range, either bound can be omitted
+    Range,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Mapping of an attribute to a URL parameter.
+pub struct AttributeMapping {
+    /// Attribute name in the index
+    pub attribute: String,
+    /// URL parameter name, defaults to the attribute name
+    pub key: String,
+    /// How the attribute is refined
+    pub kind: AttributeKind,
+}
+
+impl AttributeMapping {
+    fn new(attribute: &str, kind: AttributeKind) -> AttributeMapping {
+        AttributeMapping {
+            attribute: attribute.to_string(),
+            key: attribute.to_string(),
+            kind,
+        }
+    }
+    /// Map a disjunctive facet, refined through `facetFilters`.
+    pub fn disjunctive(attribute: &str) -> AttributeMapping {
+        AttributeMapping::new(attribute, AttributeKind::Disjunctive)
+    }
+    /// Map a conjunctive facet, refined through `facetFilters`.
+    pub fn conjunctive(attribute: &str) -> AttributeMapping {
+        AttributeMapping::new(attribute, AttributeKind::Conjunctive)
+    }
+    /// Map a numeric range, refined through `numericFilters`.
+    pub fn range(attribute: &str) -> AttributeMapping {
+        AttributeMapping::new(attribute, AttributeKind::Range)
+    }
+    /// Use another URL parameter name.
+    pub fn key(mut self, key: &str) -> AttributeMapping {
+        self.key = key.to_string();
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// State decoded from a URL.
+pub struct UrlState {
+    /// Index (or replica, for sorting) to query, `None` for the default index
+    pub index_name: Option<String>,
+    /// Search parameters
+    pub query: SearchQuery,
+}
+
+#[derive(Clone, Debug, Builder)]
+#[builder(default)]
+/// URL state codec. The page is 1-based in URLs and omitted for the first page,
+/// like InstantSearch does.
+pub struct UrlStateCodec {
+    #[builder(setter(into))]
+    /// URL parameter of the query, `query` by default
+    query_key: String,
+    #[builder(setter(into))]
+    /// URL parameter of the page, `page` by default
+    page_key: String,
+    #[builder(setter(into))]
+    /// URL parameter of the number of hits per page, `hitsPerPage` by default
+    hits_per_page_key: String,
+    #[builder(setter(into))]
+    /// URL parameter of the index, `sortBy` by default
+    index_key: String,
+    /// Index omitted from URLs
+    default_index: Option<String>,
+    /// Refined attributes
+    attributes: Vec<AttributeMapping>,
+}
+
+impl Default for UrlStateCodec {
+    fn default() -> UrlStateCodec {
+        UrlStateCodec {
+            query_key: "query".to_string(),
+            page_key: "page".to_string(),
+            hits_per_page_key: "hitsPerPage".to_string(),
+            index_key: "sortBy".to_string(),
+            default_index: None,
+            attributes: vec![],
+        }
+    }
+}
+
+impl UrlStateCodec {
+    /// Encode a query as a URL query string. Parameters of the query that
+    /// are not part of the mapping are left out.
+    pub fn encode(&self, index_name: Option<&str>, query: &SearchQuery) -> String {
+        let mut pairs: Vec<(&str, String)> = vec![];
+        if let Some(value) = query.query.as_ref().filter(|query| !query.is_empty()) {
+            pairs.push((&self.query_key, value.clone()));
+        }
+        if let Some(page) = query.page.filter(|page| *page > 0) {
+            pairs.push((&self.page_key, (page + 1).to_string()));
+        }
+        if let Some(hits_per_page) = query.hits_per_page {
+            pairs.push((&self.hits_per_page_key, hits_per_page.to_string()));
+        }
+        if let Some(index_name) =
+            index_name.filter(|name| Some(*name) != self.default_index.as_deref())
+        {
+            pairs.push((&self.index_key, index_name.to_string()));
+        }
+
+        let facet_filters = query.facet_filters.as_deref().unwrap_or_default();
+        let numeric_filters = query.numeric_filters.as_deref().unwrap_or_default();
+        for mapping in &self.attributes {
+            match mapping.kind {
+                AttributeKind::Disjunctive | AttributeKind::Conjunctive => {
+                    for filter in facet_filters {
+                        let values = match filter {
+                            StringOrVecOfString::String(value) => std::slice::from_ref(value),
+                            StringOrVecOfString::VecOfString(values) => values.as_slice(),
+                        };
+                        for value in values {
+                            if let Some(value) = facet_value(value, &mapping.attribute) {
+                                pairs.push((&mapping.key, value.to_string()));
+                            }
+                        }
+                    }
+                }
+                AttributeKind::Range => {
+                    let (min, max) = numeric_range(numeric_filters, &mapping.attribute);
+                    if min.is_some() || max.is_some() {
+                        let bound = |bound: Option<f64>| bound.map(|b| b.to_string());
+                        pairs.push((
+                            &mapping.key,
+                            format!(
+                                "{}:{}",
+                                bound(min).unwrap_or_default(),
+                                bound(max).unwrap_or_default()
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+        serde_urlencoded::to_string(pairs).expect("failed to encode url state")
+    }
+
+    /// Decode a URL query string, with or without its leading `?`. Unknown parameters
+    /// and invalid values are ignored.
+    pub fn decode(&self, query_string: &str) -> UrlState {
+        let pairs = serde_urlencoded::from_str::<Vec<(String, String)>>(
+            query_string.trim_start_matches('?'),
+        )
+        .unwrap_or_default();
+        let mut state = UrlState::default();
+        let mut facet_filters = vec![];
+        let mut numeric_filters = vec![];
+
+        for (key, value) in &pairs {
+            if *key == self.query_key {
+                state.query.query = Some(value.clone());
+            } else if *key == self.page_key {
+                state.query.page = value.parse::<u64>().ok().map(|page| page.saturating_sub(1));
+            } else if *key == self.hits_per_page_key {
+                state.query.hits_per_page = value.parse().ok();
+            } else if *key == self.index_key {
+                state.index_name = Some(value.clone());
+            }
+        }
+        for mapping in &self.attributes {
+            let mut values = pairs
+                .iter()
+                .filter(|(key, _)| *key == mapping.key)
+                .map(|(_, value)| value);
+            match mapping.kind {
+                AttributeKind::Disjunctive => {
+                    let values = values
+                        .map(|value| format!("{}:{}", mapping.attribute, value))
+                        .collect::<Vec<_>>();
+                    if !values.is_empty() {
+                        facet_filters.push(StringOrVecOfString::VecOfString(values));
+                    }
+                }
+                AttributeKind::Conjunctive => facet_filters.extend(values.map(|value| {
+                    StringOrVecOfString::String(format!("{}:{}", mapping.attribute, value))
+                })),
+                AttributeKind::Range => {
+                    if let Some((min, max)) =
+                        values.next_back().and_then(|value| value.split_once(':'))
+                    {
+                        if let Ok(min) = min.parse::<f64>() {
+                            numeric_filters.push(format!("{}>={}", mapping.attribute, min));
+                        }
+                        if let Ok(max) = max.parse::<f64>() {
+                            numeric_filters.push(format!("{}<={}", mapping.attribute, max));
+                        }
+                    }
+                }
+            }
+        }
+        if !facet_filters.is_empty() {
+            state.query.facet_filters = Some(facet_filters);
+        }
+        if !numeric_filters.is_empty() {
+            state.query.numeric_filters = Some(numeric_filters);
+        }
+        state
+    }
+}
+
+// Value of a positive `attribute:value` facet filter.
+fn facet_value<'a>(filter: &'a str, attribute: &str) -> Option<&'a str> {
+    filter
+        .strip_prefix(attribute)
+        .and_then(|rest| rest.strip_prefix(':'))
+}
+
+// Bounds of an attribute from `attribute>=min` and `attribute<=max` numeric filters.
+fn numeric_range(filters: &[String], attribute: &str) -> (Option<f64>, Option<f64>) {
+    let mut range = (None, None);
+    for filter in filters {
+        let filter: String = filter.chars().filter(|c| !c.is_whitespace()).collect();
+        if let Some(rest) = filter.strip_prefix(attribute) {
+            if let Some(min) = rest.strip_prefix(">=") {
+                range.0 = min.parse().ok().or(range.0);
+            } else if let Some(max) = rest.strip_prefix("<=") {
+                range.1 = max.parse().ok().or(range.1);
+            }
+        }
+    }
+    range
+}
+
+#[cfg(test)]
+mod url_state_tests {
+    use super::*;
+    use crate::SearchQueryBuilder;
+
+    fn codec() -> UrlStateCodec {
+        UrlStateCodecBuilder::default()
+            .query_key("q")
+            .default_index(Some("products".to_string()))
+            .attributes(vec![
+                AttributeMapping::disjunctive("brand"),
+                AttributeMapping::conjunctive("tags").key("tag"),
+                AttributeMapping::range("price"),
+            ])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_encode() {
+        let query = SearchQueryBuilder::default()
+            .query("red phone".to_string())
+            .page(0)
+            .hits_per_page(20)
+            .facet_filters(vec![
+                StringOrVecOfString::VecOfString(vec![
+                    "brand:Apple".to_string(),
+                    "brand:Samsung".to_string(),
+                ]),
+                StringOrVecOfString::String("tags:new".to_string()),
+                StringOrVecOfString::String("color:red".to_string()),
+            ])
+            .numeric_filters(vec!["price >= 10".to_string(), "price<=99.5".to_string()])
+            .build()
+            .unwrap();
+        assert_eq!(
+            codec().encode(Some("products"), &query),
+            "q=red+phone&hitsPerPage=20&brand=Apple&brand=Samsung&tag=new&price=10%3A99.5"
+        );
+        assert_eq!(
+            codec().encode(Some("products_price_asc"), &SearchQuery::default()),
+            "sortBy=products_price_asc"
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let state = codec().decode(
+            "?q=phone&page=3&tag=new&tag=promo&price=:50&brand=Apple&utm_source=x&hitsPerPage=x",
+        );
+        assert_eq!(
+            state,
+            UrlState {
+                index_name: None,
+                query: SearchQueryBuilder::default()
+                    .query("phone".to_string())
+                    .page(2)
+                    .facet_filters(vec![
+                        StringOrVecOfString::VecOfString(vec!["brand:Apple".to_string()]),
+                        StringOrVecOfString::String("tags:new".to_string()),
+                        StringOrVecOfString::String("tags:promo".to_string()),
+                    ])
+                    .numeric_filters(vec!["price<=50".to_string()])
+                    .build()
+                    .unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let url =
+            "q=phone&page=2&hitsPerPage=10&sortBy=products_desc&brand=Apple&tag=new&price=5%3A";
+        let state = codec().decode(url);
+        assert_eq!(
+            codec().encode(state.index_name.as_deref(), &state.query),
+            url
+        );
+    }
+}
@@ -0,0 +1,719 @@
+//! [Insights API](https://www.algolia.com/doc/rest-api/insights/) client, to send
+//! click, conversion and view events.
+//!
+//! Events can be sent directly with [Insights::send_events](struct.Insights.html#method.send_events)
+//! or through an [EventBuffer](struct.EventBuffer.html) that batches them in the background:
+//! ```no_run
+//! # use algoliasearch::{insights::{EventBuffer, EventBufferConfig, InsightsEvent}, Client, Error};
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<Error>> {
+//! let insights = Client::default().init_insights(None);
+//! let (buffer, worker) = EventBuffer::new(insights, EventBufferConfig::default());
+//! tokio::spawn(worker);
+//!
+//! buffer.push(InsightsEvent::clicked_object_ids_after_search(
+//!     "Product Clicked",
+//!     "products",
+//!     "user-42",
+//!     "43b15df305339e827f0ac0bdc5ebcaa7",
+//!     vec!["9780545139700".to_string()],
+//!     vec![7],
+//! )?)?;
+//!
+//! // flush what is left before exiting
+//! buffer.shutdown().await?;
+//! #   Ok(())
+//! # }
+//! ```
+use std::{
+    future::Future,
+    mem,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use futures::{
+    channel::{mpsc, oneshot},
+    StreamExt,
+};
+
+use crate::{
+    error::Error,
+    request::{auth_headers, TransportExt},
+    runtime,
+    transport::Transport,
+};
+
+/// Maximum number of events per request.
+const MAX_EVENTS_PER_REQUEST: usize = 1000;
+
+enum_str!(EventType {
+    Click("click"),
+    Conversion("conversion"),
+    View("view"),
+});
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// An insights event, build it with one of the constructors.
+pub struct InsightsEvent {
+    /// Event type
+    pub event_type: EventType,
+    /// Name of the event, e.g. `Product Clicked`
+    pub event_name: String,
+    /// Index the event relates to
+    pub index: String,
+    /// User identifier, the same as the one used in search queries
+    pub user_token: String,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "chrono::serde::ts_milliseconds_option"
+    )]
+    /// Time of the event, defaults to the time it is received
+    pub timestamp: Option<DateTime<Utc>>,
+    #[serde(rename = "queryID", skip_serializing_if = "Option::is_none")]
+    /// Query ID of the search the event follows, see [clickAnalytics](https://www.algolia.com/doc/api-reference/api-parameters/clickAnalytics/)
+    pub query_id: Option<String>,
+    #[serde(rename = "objectIDs", skip_serializing_if = "Option::is_none")]
+    /// Objects the event relates to
+    pub object_ids: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Positions of the objects in the search results
+    pub positions: Option<Vec<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Filters the event relates to, e.g. `brand:apple`
+    pub filters: Option<Vec<String>>,
+}
+
+impl InsightsEvent {
+    fn new(event_type: EventType, event_name: &str, index: &str, user_token: &str) -> Self {
+        InsightsEvent {
+            event_type,
+            event_name: event_name.to_owned(),
+            index: index.to_owned(),
+            user_token: user_token.to_owned(),
+            timestamp: None,
+            query_id: None,
+            object_ids: None,
+            positions: None,
+            filters: None,
+        }
+    }
+    /// Click on search results, `positions` being those of `object_ids`.
+    /// Fails if they have different lengths.
+    pub fn clicked_object_ids_after_search(
+        event_name: &str,
+        index: &str,
+        user_token: &str,
+        query_id: &str,
+        object_ids: Vec<String>,
+        positions: Vec<u64>,
+    ) -> Result<Self, Error> {
+        if object_ids.len() != positions.len() {
+            return Err(Error::InvalidEvent(format!(
+                "{} object IDs for {} positions",
+                object_ids.len(),
+                positions.len()
+            )));
+        }
+        Ok(InsightsEvent {
+            query_id: Some(query_id.to_owned()),
+            object_ids: Some(object_ids),
+            positions: Some(positions),
+            ..InsightsEvent::new(EventType::Click, event_name, index, user_token)
+        })
+    }
+    /// Click on objects, outside of a search.
+    pub fn clicked_object_ids(
+        event_name: &str,
+        index: &str,
+        user_token: &str,
+        object_ids: Vec<String>,
+    ) -> Self {
+        InsightsEvent {
+            object_ids: Some(object_ids),
+            ..InsightsEvent::new(EventType::Click, event_name, index, user_token)
+        }
+    }
+    /// Click on filters.
+    pub fn clicked_filters(
+        event_name: &str,
+        index: &str,
+        user_token: &str,
+        filters: Vec<String>,
+    ) -> Self {
+        InsightsEvent {
+            filters: Some(filters),
+            ..InsightsEvent::new(EventType::Click, event_name, index, user_token)
+        }
+    }
+    /// Conversion of search results.
+    pub fn converted_object_ids_after_search(
+        event_name: &str,
+        index: &str,
+        user_token: &str,
+        query_id: &str,
+        object_ids: Vec<String>,
+    ) -> Self {
+        InsightsEvent {
+            query_id: Some(query_id.to_owned()),
+            object_ids: Some(object_ids),
+            ..InsightsEvent::new(EventType::Conversion, event_name, index, user_token)
+        }
+    }
+    /// Conversion of objects, outside of a search.
+    pub fn converted_object_ids(
+        event_name: &str,
+        index: &str,
+        user_token: &str,
+        object_ids: Vec<String>,
+    ) -> Self {
+        InsightsEvent {
+            object_ids: Some(object_ids),
+            ..InsightsEvent::new(EventType::Conversion, event_name, index, user_token)
+        }
+    }
+    /// Conversion of filters.
+    pub fn converted_filters(
+        event_name: &str,
+        index: &str,
+        user_token: &str,
+        filters: Vec<String>,
+    ) -> Self {
+        InsightsEvent {
+            filters: Some(filters),
+            ..InsightsEvent::new(EventType::Conversion, event_name, index, user_token)
+        }
+    }
+    /// View of objects.
+    pub fn viewed_object_ids(
+        event_name: &str,
+        index: &str,
+        user_token: &str,
+        object_ids: Vec<String>,
+    ) -> Self {
+        InsightsEvent {
+            object_ids: Some(object_ids),
+            ..InsightsEvent::new(EventType::View, event_name, index, user_token)
+        }
+    }
+    /// View of filters.
+    pub fn viewed_filters(
+        event_name: &str,
+        index: &str,
+        user_token: &str,
+        filters: Vec<String>,
+    ) -> Self {
+        InsightsEvent {
+            filters: Some(filters),
+            ..InsightsEvent::new(EventType::View, event_name, index, user_token)
+        }
+    }
+    /// Set the time of the event.
+    pub fn timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct InsightsEvents<'a> {
+    events: &'a [InsightsEvent],
+}
+
+#[derive(Debug, Deserialize)]
+/// Result of sending events
+pub struct InsightsResult {
+    /// Http status
+    pub status: u16,
+    /// Message
+    pub message: String,
+}
+
+#[derive(Clone, Debug)]
+/// Insights client
+pub struct Insights {
+    pub(crate) application_id: String,
+    pub(crate) api_key: Option<String>,
+    pub(crate) base_url: String,
+    pub(crate) transport: Arc<dyn Transport>,
+}
+
+impl Insights {
+    /// Send events. Events are sent in batches of 1000, the maximum allowed by the API.
+    pub async fn send_events(&self, events: &[InsightsEvent]) -> Result<InsightsResult, Error> {
+        self.send_batches(events, &mut 0).await
+    }
+    // Send events in batches, counting in `sent` those of the batches that succeeded.
+    async fn send_batches(
+        &self,
+        events: &[InsightsEvent],
+        sent: &mut usize,
+    ) -> Result<InsightsResult, Error> {
+        let uri = format!("{}/events", self.base_url);
+        let mut result = InsightsResult {
+            status: 200,
+            message: "OK".to_string(),
+        };
+        for events in events.chunks(MAX_EVENTS_PER_REQUEST) {
+            result = self
+                .transport
+                .post(&uri)
+                .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+                .json(&InsightsEvents { events })
+                .send_json()
+                .await?;
+            *sent += events.len();
+        }
+        Ok(result)
+    }
+}
+
+#[derive(Clone, Debug)]
+/// [EventBuffer](struct.EventBuffer.html) configuration.
+pub struct EventBufferConfig {
+    /// Number of buffered events triggering a flush, 100 by default.
+    /// After a failed flush, the events wait for the next interval
+    pub max_events: usize,
+    /// Maximum time between two flushes, 5 seconds by default
+    pub flush_interval: Duration,
+    /// Events kept for a retry when a background flush fails,
+    /// older events are dropped beyond it and counted in
+    /// [dropped_events](struct.EventBuffer.html#method.dropped_events).
+    /// 10 000 by default
+    pub max_retained_events: usize,
+}
+
+impl Default for EventBufferConfig {
+    fn default() -> EventBufferConfig {
+        EventBufferConfig {
+            max_events: 100,
+            flush_interval: Duration::from_secs(5),
+            max_retained_events: 10_000,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Message {
+    Event(InsightsEvent),
+    Flush(oneshot::Sender<Result<(), Error>>),
+    Shutdown(oneshot::Sender<Result<(), Error>>),
+}
+
+#[derive(Clone, Debug)]
+/// In-process event buffer, flushing events when it holds
+/// [max_events](struct.EventBufferConfig.html#structfield.max_events) of them
+/// or every [flush_interval](struct.EventBufferConfig.html#structfield.flush_interval).
+pub struct EventBuffer {
+    sender: mpsc::UnboundedSender<Message>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl EventBuffer {
+    /// Create a buffer and the worker future sending its events,
+    /// which must be spawned on the runtime.
+    pub fn new(
+        insights: Insights,
+        config: EventBufferConfig,
+    ) -> (EventBuffer, impl Future<Output = ()> + Send + 'static) {
+        let (sender, receiver) = mpsc::unbounded();
+        let dropped = Arc::new(AtomicU64::new(0));
+        let worker = run(insights, config, receiver, dropped.clone());
+        (EventBuffer { sender, dropped }, worker)
+    }
+    /// Add an event to the buffer. Fails once the buffer is shut down.
+    pub fn push(&self, event: InsightsEvent) -> Result<(), Error> {
+        self.sender
+            .unbounded_send(Message::Event(event))
+            .map_err(|_| Error::BufferClosed)
+    }
+    /// Send the buffered events now.
+    pub async fn flush(&self) -> Result<(), Error> {
+        let (ack, done) = oneshot::channel();
+        self.sender
+            .unbounded_send(Message::Flush(ack))
+            .map_err(|_| Error::BufferClosed)?;
+        done.await.map_err(|_| Error::BufferClosed)?
+    }
+    /// Send the buffered events and stop the worker.
+    /// Events pushed through other handles afterwards are rejected.
+    pub async fn shutdown(self) -> Result<(), Error> {
+        let (ack, done) = oneshot::channel();
+        self.sender
+            .unbounded_send(Message::Shutdown(ack))
+            .map_err(|_| Error::BufferClosed)?;
+        done.await.map_err(|_| Error::BufferClosed)?
+    }
+    /// Number of events dropped so far because background flushes failed
+    /// while more than
+    /// [max_retained_events](struct.EventBufferConfig.html#structfield.max_retained_events)
+    /// events were waiting.
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+async fn run(
+    insights: Insights,
+    config: EventBufferConfig,
+    mut receiver: mpsc::UnboundedReceiver<Message>,
+    dropped: Arc<AtomicU64>,
+) {
+    let mut worker = Worker {
+        deadline: Instant::now() + config.flush_interval,
+        insights,
+        config,
+        events: vec![],
+        failing: false,
+        dropped,
+    };
+    loop {
+        match runtime::timeout(
+            worker.deadline.saturating_duration_since(Instant::now()),
+            receiver.next(),
+        )
+        .await
+        {
+            Some(Some(Message::Event(event))) => {
+                worker.events.push(event);
+                // after a failure, wait for the interval before trying again
+                if worker.events.len() >= worker.config.max_events && !worker.failing {
+                    worker.background_flush().await;
+                }
+            }
+            Some(Some(Message::Flush(ack))) => {
+                let _ = ack.send(worker.flush().await);
+            }
+            Some(Some(Message::Shutdown(ack))) => {
+                receiver.close();
+                // messages sent through other handles before the channel was closed
+                let mut shutdown_acks = vec![];
+                while let Ok(Some(message)) = receiver.try_next() {
+                    match message {
+                        Message::Event(event) => worker.events.push(event),
+                        Message::Flush(ack) => {
+                            let _ = ack.send(worker.flush().await);
+                        }
+                        Message::Shutdown(ack) => shutdown_acks.push(ack),
+                    }
+                }
+                let _ = ack.send(worker.flush().await);
+                for ack in shutdown_acks {
+                    let _ = ack.send(Err(Error::BufferClosed));
+                }
+                return;
+            }
+            // every handle was dropped
+            Some(None) => {
+                worker.background_flush().await;
+                return;
+            }
+            None => worker.background_flush().await,
+        }
+    }
+}
+
+// State of the task sending the events of a buffer.
+struct Worker {
+    insights: Insights,
+    config: EventBufferConfig,
+    events: Vec<InsightsEvent>,
+    deadline: Instant,
+    // whether the last flush failed
+    failing: bool,
+    dropped: Arc<AtomicU64>,
+}
+
+impl Worker {
+    async fn flush(&mut self) -> Result<(), Error> {
+        let result = flush(&self.insights, &mut self.events).await;
+        let overflow = self
+            .events
+            .len()
+            .saturating_sub(self.config.max_retained_events);
+        if overflow > 0 {
+            self.events.drain(..overflow);
+            self.dropped.fetch_add(overflow as u64, Ordering::Relaxed);
+        }
+        self.failing = result.is_err();
+        self.deadline = Instant::now() + self.config.flush_interval;
+        result
+    }
+
+    // Flush nobody waits for, the error can only be logged.
+    async fn background_flush(&mut self) {
+        if let Err(error) = self.flush().await {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                ?error,
+                retained = self.events.len(),
+                dropped = self.dropped.load(Ordering::Relaxed),
+                "insights flush failed"
+            );
+            #[cfg(not(feature = "tracing"))]
+            let _ = error;
+        }
+    }
+}
+
+// Send the buffered events, keeping those that were not sent for a retry if it fails.
+async fn flush(insights: &Insights, events: &mut Vec<InsightsEvent>) -> Result<(), Error> {
+    if events.is_empty() {
+        return Ok(());
+    }
+    let mut batch = mem::take(events);
+    let mut sent = 0;
+    match insights.send_batches(&batch, &mut sent).await {
+        Ok(_) => Ok(()),
+        Err(err) => {
+            batch.drain(..sent);
+            *events = batch;
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod insights_tests {
+    use super::*;
+    use chrono::TimeZone;
+    use futures::future::BoxFuture;
+    use serde_json;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    use crate::transport::{HeaderMap, HttpRequest, HttpResponse};
+
+    #[test]
+    fn test_serialize() {
+        let event = InsightsEvent::clicked_object_ids_after_search(
+            "Product Clicked",
+            "products",
+            "user-42",
+            "43b15df305339e827f0ac0bdc5ebcaa7",
+            vec!["1".to_string(), "2".to_string()],
+            vec![3, 7],
+        )
+        .unwrap()
+        .timestamp(Utc.timestamp_opt(1_700_000_000, 0).unwrap());
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"eventType":"click","eventName":"Product Clicked","index":"products","userToken":"user-42","timestamp":1700000000000,"queryID":"43b15df305339e827f0ac0bdc5ebcaa7","objectIDs":["1","2"],"positions":[3,7]}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&InsightsEvent::viewed_filters(
+                "Filter Viewed",
+                "products",
+                "user-42",
+                vec!["brand:apple".to_string()]
+            ))
+            .unwrap(),
+            r#"{"eventType":"view","eventName":"Filter Viewed","index":"products","userToken":"user-42","filters":["brand:apple"]}"#
+        );
+    }
+
+    #[test]
+    fn test_positions_mismatch() {
+        let event = InsightsEvent::clicked_object_ids_after_search(
+            "Product Clicked",
+            "products",
+            "user-42",
+            "query",
+            vec!["1".to_string()],
+            vec![],
+        );
+        assert!(matches!(event, Err(Error::InvalidEvent(_))));
+    }
+
+    // Records the number of events of each request, failing the requests in `failures`.
+    #[derive(Debug, Default)]
+    struct Events {
+        sent: Mutex<Vec<usize>>,
+        failures: Mutex<Vec<usize>>,
+        requests: AtomicUsize,
+    }
+
+    impl Transport for Arc<Events> {
+        fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+            let request_number = self.requests.fetch_add(1, Ordering::SeqCst);
+            let (status, body) = if self.failures.lock().unwrap().contains(&request_number) {
+                (500, r#"{"status":500,"message":"Internal error"}"#)
+            } else {
+                let body: serde_json::Value =
+                    serde_json::from_slice(request.body.as_ref().unwrap()).unwrap();
+                let events = body["events"].as_array().unwrap().len();
+                self.sent.lock().unwrap().push(events);
+                (200, r#"{"status":200,"message":"OK"}"#)
+            };
+            Box::pin(async move {
+                Ok(HttpResponse {
+                    status,
+                    headers: HeaderMap::new(),
+                    body: body.as_bytes().to_vec(),
+                })
+            })
+        }
+    }
+
+    fn buffer(events: &Arc<Events>, config: EventBufferConfig) -> EventBuffer {
+        let insights = Insights {
+            application_id: "APP".to_string(),
+            api_key: Some("KEY".to_string()),
+            base_url: "https://insights.algolia.io/1".to_string(),
+            transport: Arc::new(events.clone()),
+        };
+        let (buffer, worker) = EventBuffer::new(insights, config);
+        tokio::spawn(worker);
+        buffer
+    }
+
+    fn event() -> InsightsEvent {
+        InsightsEvent::viewed_object_ids(
+            "Product Viewed",
+            "products",
+            "user-42",
+            vec!["1".to_string()],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_size_flush() {
+        let events = Arc::new(Events::default());
+        let buffer = buffer(
+            &events,
+            EventBufferConfig {
+                max_events: 2,
+                flush_interval: Duration::from_secs(3600),
+                ..Default::default()
+            },
+        );
+        for _ in 0..5 {
+            buffer.push(event()).unwrap();
+        }
+        runtime::sleep(Duration::from_millis(50)).await;
+        assert_eq!(*events.sent.lock().unwrap(), vec![2, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_size_flush() {
+        let events = Arc::new(Events::default());
+        *events.failures.lock().unwrap() = vec![0];
+        let buffer = buffer(
+            &events,
+            EventBufferConfig {
+                max_events: 2,
+                flush_interval: Duration::from_millis(100),
+                ..Default::default()
+            },
+        );
+        for _ in 0..10 {
+            buffer.push(event()).unwrap();
+        }
+        runtime::sleep(Duration::from_millis(50)).await;
+        // no retry on each push while the first flush failed
+        assert_eq!(events.requests.load(Ordering::SeqCst), 1);
+        runtime::sleep(Duration::from_millis(100)).await;
+        assert_eq!(*events.sent.lock().unwrap(), vec![10]);
+    }
+
+    #[tokio::test]
+    async fn test_interval_flush() {
+        let events = Arc::new(Events::default());
+        let buffer = buffer(
+            &events,
+            EventBufferConfig {
+                flush_interval: Duration::from_millis(50),
+                ..Default::default()
+            },
+        );
+        buffer.push(event()).unwrap();
+        assert!(events.sent.lock().unwrap().is_empty());
+        runtime::sleep(Duration::from_millis(150)).await;
+        assert_eq!(*events.sent.lock().unwrap(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown() {
+        let events = Arc::new(Events::default());
+        let buffer = buffer(&events, EventBufferConfig::default());
+        let other = buffer.clone();
+        buffer.push(event()).unwrap();
+        buffer.push(event()).unwrap();
+        buffer.shutdown().await.unwrap();
+        assert_eq!(*events.sent.lock().unwrap(), vec![2]);
+        assert!(matches!(other.push(event()), Err(Error::BufferClosed)));
+    }
+
+    #[tokio::test]
+    async fn test_push_during_shutdown() {
+        let events = Arc::new(Events::default());
+        let buffer = buffer(&events, EventBufferConfig::default());
+        let other = buffer.clone();
+        buffer.push(event()).unwrap();
+        let mut shutdown = Box::pin(buffer.shutdown());
+        // the shutdown message is sent, but the worker didn't run yet
+        assert!(futures::poll!(&mut shutdown).is_pending());
+        other.push(event()).unwrap();
+        shutdown.await.unwrap();
+        assert_eq!(*events.sent.lock().unwrap(), vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_flush() {
+        let events = Arc::new(Events::default());
+        // the second batch of 1000 events fails
+        *events.failures.lock().unwrap() = vec![1];
+        let buffer = buffer(
+            &events,
+            EventBufferConfig {
+                max_events: 10_000,
+                flush_interval: Duration::from_secs(3600),
+                ..Default::default()
+            },
+        );
+        for _ in 0..1500 {
+            buffer.push(event()).unwrap();
+        }
+        assert!(matches!(
+            buffer.flush().await,
+            Err(Error::Api { status: 500, .. })
+        ));
+        assert_eq!(*events.sent.lock().unwrap(), vec![1000]);
+        // only the events that were not sent are sent again
+        buffer.flush().await.unwrap();
+        assert_eq!(*events.sent.lock().unwrap(), vec![1000, 500]);
+        assert_eq!(buffer.dropped_events(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_events() {
+        let events = Arc::new(Events::default());
+        *events.failures.lock().unwrap() = vec![0, 1];
+        let buffer = buffer(
+            &events,
+            EventBufferConfig {
+                max_events: 10_000,
+                flush_interval: Duration::from_secs(3600),
+                max_retained_events: 3,
+            },
+        );
+        for _ in 0..5 {
+            buffer.push(event()).unwrap();
+        }
+        assert!(buffer.flush().await.is_err());
+        assert_eq!(buffer.dropped_events(), 2);
+        buffer.push(event()).unwrap();
+        assert!(buffer.flush().await.is_err());
+        assert_eq!(buffer.dropped_events(), 3);
+        // only the retained events are sent
+        buffer.flush().await.unwrap();
+        assert_eq!(*events.sent.lock().unwrap(), vec![3]);
+    }
+}
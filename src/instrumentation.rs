@@ -0,0 +1,288 @@
+//! Tracing spans and metrics of the index operations, see the features of the crate.
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+use std::time::Instant;
+
+use crate::{
+    error::Error,
+    transport::{HttpRequest, HttpResponse, Transport},
+};
+
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+#[derive(Debug)]
+pub(crate) struct Operation {
+    name: &'static str,
+    index_name: String,
+}
+
+impl Operation {
+    pub(crate) fn new(name: &'static str, index_name: &str) -> Operation {
+        Operation {
+            name,
+            index_name: index_name.to_string(),
+        }
+    }
+
+    /// Send the request of the operation, recording it.
+    pub(crate) async fn run(
+        self,
+        transport: &dyn Transport,
+        request: HttpRequest,
+    ) -> Result<HttpResponse, Error> {
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "algolia",
+            operation = self.name,
+            index_name = %self.index_name,
+            host = host(&request.url),
+            retries = 0u64,
+            status = tracing::field::Empty,
+            processing_time_ms = tracing::field::Empty,
+            nb_hits = tracing::field::Empty,
+        );
+        #[cfg(any(feature = "tracing", feature = "metrics"))]
+        let start = Instant::now();
+
+        let response = transport.send(request);
+        #[cfg(feature = "tracing")]
+        let response = tracing::Instrument::instrument(response, span.clone());
+        let response = response.await;
+
+        #[cfg(feature = "tracing")]
+        record_span(&span, &response, start.elapsed());
+        #[cfg(feature = "metrics")]
+        record_metrics(self.name, &response, start.elapsed());
+        response
+    }
+}
+
+// Host of a URL, without scheme nor path.
+#[cfg(feature = "tracing")]
+fn host(url: &str) -> &str {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    url.split(['/', '?']).next().unwrap_or(url)
+}
+
+#[cfg(feature = "tracing")]
+#[derive(Deserialize)]
+struct ResponseStats {
+    #[serde(rename = "processingTimeMS")]
+    processing_time_ms: Option<u64>,
+    #[serde(rename = "nbHits")]
+    nb_hits: Option<u64>,
+}
+
+#[cfg(feature = "tracing")]
+fn record_span(
+    span: &tracing::Span,
+    response: &Result<HttpResponse, Error>,
+    elapsed: std::time::Duration,
+) {
+    let _entered = span.enter();
+    match response {
+        Ok(response) => {
+            span.record("status", response.status);
+            if let Ok(stats) = serde_json::from_slice::<ResponseStats>(&response.body) {
+                if let Some(processing_time_ms) = stats.processing_time_ms {
+                    span.record("processing_time_ms", processing_time_ms);
+                }
+                if let Some(nb_hits) = stats.nb_hits {
+                    span.record("nb_hits", nb_hits);
+                }
+            }
+            tracing::debug!(elapsed_ms = elapsed.as_millis() as u64, "response received");
+        }
+        Err(error) => tracing::warn!(?error, "request failed"),
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn record_metrics(
+    operation: &'static str,
+    response: &Result<HttpResponse, Error>,
+    elapsed: std::time::Duration,
+) {
+    let status = match response {
+        Ok(response) => response.status.to_string(),
+        Err(_) => "error".to_string(),
+    };
+    metrics::counter!("algolia_requests_total", "operation" => operation, "status" => status)
+        .increment(1);
+    metrics::histogram!("algolia_request_duration_seconds", "operation" => operation)
+        .record(elapsed.as_secs_f64());
+}
+
+#[cfg(all(test, any(feature = "tracing", feature = "metrics")))]
+mod instrumentation_tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    use futures::future::BoxFuture;
+    use reqwest::header::RETRY_AFTER;
+
+    use crate::{
+        rate_limit::{RateLimit, RateLimitedTransport, RateLimiter},
+        transport::{HeaderMap, Method},
+    };
+
+    // Answers a search with a 429 first, then with its stats.
+    #[derive(Debug, Default)]
+    struct Throttled(Mutex<bool>);
+
+    impl Transport for Throttled {
+        fn send(&self, _: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+            let throttled = !std::mem::replace(&mut *self.0.lock().unwrap(), true);
+            Box::pin(async move {
+                let mut headers = HeaderMap::new();
+                headers.insert(RETRY_AFTER, "0".parse().unwrap());
+                Ok(if throttled {
+                    HttpResponse {
+                        status: 429,
+                        headers,
+                        body: vec![],
+                    }
+                } else {
+                    HttpResponse {
+                        status: 200,
+                        headers,
+                        body: br#"{"hits":[],"nbHits":42,"processingTimeMS":3}"#.to_vec(),
+                    }
+                })
+            })
+        }
+    }
+
+    // Run a search through a rate limited transport retrying the 429.
+    fn search() -> HttpResponse {
+        let transport = RateLimitedTransport {
+            transport: Arc::new(Throttled::default()),
+            limiter: Arc::new(RateLimiter::new(&RateLimit::default())),
+        };
+        let request = HttpRequest {
+            method: Method::POST,
+            url: "https://APP-dsn.algolia.net/1/indexes/products/query".to_string(),
+            headers: HeaderMap::new(),
+            body: None,
+            timeout: None,
+        };
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(Operation::new("search", "products").run(&transport, request))
+            .unwrap()
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_host() {
+        assert_eq!(
+            host("https://APP-dsn.algolia.net/1/indexes/products/query"),
+            "APP-dsn.algolia.net"
+        );
+        assert_eq!(host("http://localhost:8080?x=1"), "localhost:8080");
+    }
+
+    // Subscriber keeping the fields of the spans as strings, and the stack of entered spans.
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Default)]
+    struct Spans {
+        spans: Arc<Mutex<Vec<(&'static tracing::Metadata<'static>, Fields)>>>,
+        entered: Arc<Mutex<Vec<tracing::span::Id>>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    type Fields = std::collections::HashMap<String, String>;
+
+    #[cfg(feature = "tracing")]
+    struct Visitor<'a>(&'a mut Fields);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for Visitor<'_> {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for Spans {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, attributes: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut spans = self.spans.lock().unwrap();
+            let mut fields = Fields::new();
+            attributes.record(&mut Visitor(&mut fields));
+            spans.push((attributes.metadata(), fields));
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+        fn record(&self, id: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            values.record(&mut Visitor(&mut spans[id.into_u64() as usize - 1].1));
+        }
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+        fn event(&self, _: &tracing::Event<'_>) {}
+        fn enter(&self, id: &tracing::span::Id) {
+            self.entered.lock().unwrap().push(id.clone());
+        }
+        fn exit(&self, _: &tracing::span::Id) {
+            self.entered.lock().unwrap().pop();
+        }
+        fn current_span(&self) -> tracing_core::span::Current {
+            match self.entered.lock().unwrap().last() {
+                Some(id) => {
+                    let metadata = self.spans.lock().unwrap()[id.into_u64() as usize - 1].0;
+                    tracing_core::span::Current::new(id.clone(), metadata)
+                }
+                None => tracing_core::span::Current::none(),
+            }
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_span() {
+        let spans = Spans::default();
+        let response = tracing::subscriber::with_default(spans.clone(), search);
+        assert_eq!(response.status, 200);
+        let spans = spans.spans.lock().unwrap();
+        assert_eq!(spans.len(), 1);
+        let fields = &spans[0].1;
+        assert_eq!(fields["operation"], "search");
+        assert_eq!(fields["index_name"], "products");
+        assert_eq!(fields["host"], "APP-dsn.algolia.net");
+        assert_eq!(fields["status"], "200");
+        assert_eq!(fields["processing_time_ms"], "3");
+        assert_eq!(fields["nb_hits"], "42");
+        assert_eq!(fields["retries"], "1");
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_metrics() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let response = metrics::with_local_recorder(&recorder, search);
+        assert_eq!(response.status, 200);
+        let metrics = snapshotter.snapshot().into_vec();
+        let (key, _, _, value) = metrics
+            .iter()
+            .find(|(key, _, _, _)| key.key().name() == "algolia_requests_total")
+            .unwrap();
+        let labels = key
+            .key()
+            .labels()
+            .map(|label| (label.key(), label.value()))
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec![("operation", "search"), ("status", "200")]);
+        assert_eq!(value, &DebugValue::Counter(1));
+        assert!(metrics.iter().any(|(key, _, _, value)| key.key().name()
+            == "algolia_request_duration_seconds"
+            && matches!(value, DebugValue::Histogram(values) if values.len() == 1)));
+    }
+}
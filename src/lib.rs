@@ -34,6 +34,17 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! # Features
+//! - `tracing`: a span `algolia` per index operation, with the fields `operation`, `index_name`,
+//!   `host`, `retries`, `status`, `processing_time_ms` and `nb_hits`.
+//! - `metrics`: the counter `algolia_requests_total`, labeled by `operation` and `status`,
+//!   and the histogram `algolia_request_duration_seconds`, labeled by `operation`.
+//! - `runtime-tokio` (default), `runtime-async-std`, `runtime-smol`: the async runtime whose
+//!   timers are used, e.g. to wait between polls. Requests are sent by reqwest on every runtime.
+//! - `gzip`: [Client::gzip](client/struct.Client.html#method.gzip), to compress large writes,
+//!   and compressed responses.
+//! - `blocking`: the [blocking](blocking/index.html) client, for programs without an async runtime.
 
 #[macro_use]
 extern crate derive_builder;
@@ -42,13 +53,36 @@ extern crate serde_derive;
 
 #[macro_use]
 mod macros;
+pub mod abtesting;
+pub mod analytics;
+pub mod api_key;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod client;
+pub mod clusters;
+#[cfg(feature = "gzip")]
+mod compression;
+pub mod credentials;
+pub mod dictionaries;
 pub mod error;
 pub mod index;
+pub mod insights;
+mod instrumentation;
+pub mod logs;
+pub mod middleware;
+pub mod personalization;
+pub mod query_suggestions;
+pub mod rate_limit;
+pub mod recommend;
+mod request;
+mod runtime;
+pub mod transport;
 
-pub use client::Client;
+pub use client::{Client, Region};
 pub use error::Error;
-pub use index::{settings, SearchQueryBuilder};
+pub use index::{cache, filters, geo, settings, url_state, SearchQueryBuilder};
+pub use request::RequestOptions;
 
 static APPLICATION_ID_HEADER: &str = "x-algolia-application-id";
 static API_KEY_HEADER: &str = "x-algolia-api-key";
+static USER_ID_HEADER: &str = "x-algolia-user-id";
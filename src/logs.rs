@@ -0,0 +1,129 @@
+//! Logs of the API calls of an application,
+//! see [Client::get_logs](../client/struct.Client.html#method.get_logs).
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{de, Deserialize, Deserializer};
+
+enum_str!(LogType {
+    All("all"),
+    Query("query"),
+    Build("build"),
+    Error("error"),
+});
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+/// A logged API call
+pub struct LogEntry {
+    /// Date
+    pub timestamp: DateTime<Utc>,
+    /// HTTP method
+    pub method: String,
+    #[serde(deserialize_with = "from_str")]
+    /// HTTP status of the answer
+    pub answer_code: u16,
+    /// Body of the request
+    pub query_body: String,
+    /// Body of the answer, truncated after 1000 characters
+    pub answer: String,
+    /// Path and query string of the request
+    pub url: String,
+    /// Client IP
+    pub ip: String,
+    /// Headers of the request, API key excluded
+    pub query_headers: String,
+    /// SHA1 of the request
+    pub sha1: String,
+    #[serde(default, deserialize_with = "option_from_str")]
+    /// Number of API calls
+    pub nb_api_calls: Option<u64>,
+    #[serde(deserialize_with = "from_str")]
+    /// Processing time (ms)
+    pub processing_time_ms: u64,
+    #[serde(default)]
+    /// Index
+    pub index: Option<String>,
+    #[serde(default)]
+    /// Search parameters, for queries
+    pub query_params: Option<String>,
+    #[serde(default, deserialize_with = "option_from_str")]
+    /// Number of hits, for queries
+    pub query_nb_hits: Option<u64>,
+}
+
+// The API sends numbers as strings.
+fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(de::Error::custom)
+}
+
+fn option_from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(de::Error::custom))
+        .transpose()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogsQuery<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) offset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) length: Option<u64>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub(crate) log_type: Option<LogType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) index_name: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct LogsResult {
+    pub(crate) logs: Vec<LogEntry>,
+}
+
+#[cfg(test)]
+mod logs_tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn test_deserialize() {
+        let res = serde_json::from_str::<LogsResult>(
+            r#"{"logs":[{"timestamp":"2024-01-02T03:04:05Z","method":"POST","answer_code":"400","query_body":"{}","answer":"{\"message\":\"bad\"}","url":"/1/indexes/products/batch","ip":"127.0.0.1","query_headers":"User-Agent: test","sha1":"26c53bd7e38ca71f4741b71994cd94a600b7ac68","nb_api_calls":"1","processing_time_ms":"3","index":"products"}]}"#,
+        )
+        .unwrap();
+        let log = &res.logs[0];
+        assert_eq!(log.answer_code, 400);
+        assert_eq!(log.nb_api_calls, Some(1));
+        assert_eq!(log.query_nb_hits, None);
+        assert!(serde_json::from_str::<LogsResult>(
+            r#"{"logs":[{"timestamp":"2024-01-02T03:04:05Z","method":"GET","answer_code":"OK","query_body":"","answer":"","url":"/","ip":"","query_headers":"","sha1":"","processing_time_ms":"1"}]}"#,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_serialize_query() {
+        assert_eq!(
+            serde_urlencoded::to_string(LogsQuery {
+                offset: None,
+                length: Some(100),
+                log_type: Some(LogType::Error),
+                index_name: Some("products"),
+            })
+            .unwrap(),
+            "length=100&type=error&indexName=products"
+        );
+    }
+}
@@ -7,14 +7,21 @@ macro_rules! enum_str {
             $($variant,)*
         }
 
+        impl $name {
+            /// String the API uses for this value.
+            pub fn as_str(&self) -> &'static str {
+                match *self {
+                    $( $name::$variant => $str, )*
+                }
+            }
+        }
+
         impl ::serde::Serialize for $name {
             fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
                 where S: ::serde::Serializer,
             {
                 // Serialize the enum as a string.
-                serializer.serialize_str(match *self {
-                    $( $name::$variant => $str, )*
-                })
+                serializer.serialize_str(self.as_str())
             }
         }
 
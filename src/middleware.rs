@@ -0,0 +1,157 @@
+//! Hooks run around every request of a [Client](../client/struct.Client.html) and of the
+//! indices and clients it initializes, see [Client::interceptor](../client/struct.Client.html#method.interceptor).
+//! ```
+//! # use std::time::Duration;
+//! # use algoliasearch::{middleware::Interceptor, transport::{HttpRequest, HttpResponse}, Client, Error};
+//! #[derive(Debug)]
+//! struct Logger;
+//!
+//! impl Interceptor for Logger {
+//!     fn before_request(&self, request: &mut HttpRequest) -> Result<(), Error> {
+//!         request.headers.insert("x-request-source", "backoffice".parse().unwrap());
+//!         println!("{} {} {:?}", request.method, request.path(), request.redacted_headers());
+//!         Ok(())
+//!     }
+//!
+//!     fn after_response(
+//!         &self,
+//!         request: &HttpRequest,
+//!         response: &mut HttpResponse,
+//!         elapsed: Duration,
+//!     ) -> Result<(), Error> {
+//!         println!("{} {} in {:?}", request.path(), response.status, elapsed);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let client = Client::new("APP_ID", "API_KEY").interceptor(Logger);
+//! ```
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use futures::future::BoxFuture;
+
+use crate::{
+    error::Error,
+    transport::{HttpRequest, HttpResponse, Transport},
+};
+
+/// Hooks of a request. Interceptors run in the order they were added before the request,
+/// and in the reverse order after the response.
+pub trait Interceptor: fmt::Debug + Send + Sync {
+    /// Inspect or change a request before it is sent. An error cancels the request.
+    fn before_request(&self, _request: &mut HttpRequest) -> Result<(), Error> {
+        Ok(())
+    }
+    /// Inspect or change a response, whatever its status, before it is decoded.
+    fn after_response(
+        &self,
+        _request: &HttpRequest,
+        _response: &mut HttpResponse,
+        _elapsed: Duration,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+// Transport running interceptors around another one.
+#[derive(Debug)]
+pub(crate) struct InterceptedTransport {
+    pub(crate) transport: Arc<dyn Transport>,
+    pub(crate) interceptors: Vec<Arc<dyn Interceptor>>,
+}
+
+impl Transport for InterceptedTransport {
+    fn send(&self, mut request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+        Box::pin(async move {
+            for interceptor in &self.interceptors {
+                interceptor.before_request(&mut request)?;
+            }
+            let start = Instant::now();
+            let mut response = self.transport.send(request.clone()).await?;
+            let elapsed = start.elapsed();
+            for interceptor in self.interceptors.iter().rev() {
+                interceptor.after_response(&request, &mut response, elapsed)?;
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod middleware_tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use crate::transport::{HeaderMap, Method};
+
+    #[derive(Debug)]
+    struct Echo;
+
+    impl Transport for Echo {
+        fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+            Box::pin(async move {
+                Ok(HttpResponse {
+                    status: 200,
+                    headers: request.headers,
+                    body: vec![],
+                })
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    struct Tag(&'static str, Arc<Mutex<Vec<String>>>);
+
+    impl Interceptor for Tag {
+        fn before_request(&self, request: &mut HttpRequest) -> Result<(), Error> {
+            request.headers.append("x-tag", self.0.parse().unwrap());
+            self.1.lock().unwrap().push(format!("before {}", self.0));
+            Ok(())
+        }
+        fn after_response(
+            &self,
+            _: &HttpRequest,
+            response: &mut HttpResponse,
+            _: Duration,
+        ) -> Result<(), Error> {
+            response.status = 201;
+            self.1.lock().unwrap().push(format!("after {}", self.0));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chain() {
+        let calls = Arc::new(Mutex::new(vec![]));
+        let transport = InterceptedTransport {
+            transport: Arc::new(Echo),
+            interceptors: vec![
+                Arc::new(Tag("a", calls.clone())),
+                Arc::new(Tag("b", calls.clone())),
+            ],
+        };
+        let response = transport
+            .send(HttpRequest {
+                method: Method::GET,
+                url: "https://example.org/1/keys".to_string(),
+                headers: HeaderMap::new(),
+                body: None,
+                timeout: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.status, 201);
+        assert_eq!(
+            response.headers.get_all("x-tag").iter().collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["before a", "before b", "after b", "after a"]
+        );
+    }
+}
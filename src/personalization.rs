@@ -0,0 +1,187 @@
+//! [Personalization API](https://www.algolia.com/doc/rest-api/personalization/) client.
+//! ```no_run
+//! # use algoliasearch::{insights::EventType, personalization::{EventScoring, FacetScoring, PersonalizationStrategy}, Client, Error, Region};
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<Error>> {
+//! let personalization = Client::default().init_personalization(Region::De);
+//! personalization
+//!     .set_strategy(&PersonalizationStrategy {
+//!         events_scoring: vec![EventScoring {
+//!             event_name: "Product Clicked".to_string(),
+//!             event_type: EventType::Click,
+//!             score: 50,
+//!         }],
+//!         facets_scoring: vec![FacetScoring {
+//!             facet_name: "brand".to_string(),
+//!             score: 100,
+//!         }],
+//!         personalization_impact: 75,
+//!     })
+//!     .await?;
+//! personalization.delete_user_profile("user-42").await?;
+//! #   Ok(())
+//! # }
+//! ```
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    error::Error,
+    insights::EventType,
+    request::{auth_headers, encode_path_segment, TransportExt},
+    transport::Transport,
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Score of an insights event
+pub struct EventScoring {
+    /// Event name, as sent to the insights API
+    pub event_name: String,
+    /// Event type
+    pub event_type: EventType,
+    /// Score, from 1 to 100
+    pub score: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Score of a facet
+pub struct FacetScoring {
+    /// Facet attribute
+    pub facet_name: String,
+    /// Score, from 1 to 100
+    pub score: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// [https://www.algolia.com/doc/guides/personalization/personalizing-results/in-depth/configuring-personalization/](https://www.algolia.com/doc/guides/personalization/personalizing-results/in-depth/configuring-personalization/)
+pub struct PersonalizationStrategy {
+    /// Scores of the events
+    pub events_scoring: Vec<EventScoring>,
+    /// Scores of the facets
+    pub facets_scoring: Vec<FacetScoring>,
+    /// Impact of personalization on the ranking, from 0 to 100
+    pub personalization_impact: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Set strategy result
+pub struct SetStrategyResult {
+    /// Date of the update
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Affinities of a user
+pub struct UserTokenProfile {
+    /// User token
+    pub user_token: String,
+    /// Date of the last event of the user
+    pub last_event_at: DateTime<Utc>,
+    /// Scores of the user by facet name, then facet value
+    pub scores: HashMap<String, HashMap<String, f64>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Delete user profile result
+pub struct DeleteUserProfileResult {
+    /// User token
+    pub user_token: String,
+    /// The profile is deleted, but may still be used for searches until this date
+    pub deleted_until: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+/// Personalization client
+pub struct Personalization {
+    pub(crate) application_id: String,
+    pub(crate) api_key: Option<String>,
+    pub(crate) base_url: String,
+    pub(crate) transport: Arc<dyn Transport>,
+}
+
+impl Personalization {
+    /// Get the personalization strategy.
+    pub async fn get_strategy(&self) -> Result<PersonalizationStrategy, Error> {
+        let uri = format!("{}/strategies/personalization", self.base_url);
+        self.transport
+            .get(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .send_json()
+            .await
+    }
+    /// Set the personalization strategy.
+    pub async fn set_strategy(
+        &self,
+        strategy: &PersonalizationStrategy,
+    ) -> Result<SetStrategyResult, Error> {
+        let uri = format!("{}/strategies/personalization", self.base_url);
+        self.transport
+            .post(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .json(strategy)
+            .send_json()
+            .await
+    }
+    /// Get the profile of a user.
+    pub async fn get_user_token_profile(
+        &self,
+        user_token: &str,
+    ) -> Result<UserTokenProfile, Error> {
+        let uri = format!(
+            "{}/profiles/personalization/{}",
+            self.base_url,
+            encode_path_segment(user_token)
+        );
+        self.transport
+            .get(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .send_json()
+            .await
+    }
+    /// Delete the profile of a user and its events.
+    pub async fn delete_user_profile(
+        &self,
+        user_token: &str,
+    ) -> Result<DeleteUserProfileResult, Error> {
+        let uri = format!(
+            "{}/profiles/{}",
+            self.base_url,
+            encode_path_segment(user_token)
+        );
+        self.transport
+            .delete(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .send_json()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod personalization_tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn test_strategy() {
+        let json = r#"{"eventsScoring":[{"eventName":"Product Viewed","eventType":"view","score":10}],"facetsScoring":[{"facetName":"brand","score":100}],"personalizationImpact":50}"#;
+        let strategy = serde_json::from_str::<PersonalizationStrategy>(json).unwrap();
+        assert_eq!(strategy.events_scoring[0].event_type, EventType::View);
+        assert_eq!(serde_json::to_string(&strategy).unwrap(), json);
+    }
+
+    #[test]
+    fn test_user_token_profile() {
+        let profile = serde_json::from_str::<UserTokenProfile>(
+            r#"{"userToken":"user-42","lastEventAt":"2024-01-05T10:00:00Z","scores":{"brand":{"Apple":3}}}"#,
+        )
+        .unwrap();
+        assert_eq!(profile.scores["brand"]["Apple"], 3.0);
+    }
+}
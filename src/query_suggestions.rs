@@ -0,0 +1,284 @@
+//! [Query Suggestions API](https://www.algolia.com/doc/rest-api/query-suggestions/) client.
+//! ```no_run
+//! # use algoliasearch::{query_suggestions::{Languages, QuerySuggestionsConfigBuilder, SourceIndexBuilder, SourceIndexFacet}, Client, Error, Region};
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<Error>> {
+//! let query_suggestions = Client::default().init_query_suggestions(Region::De);
+//! let config = QuerySuggestionsConfigBuilder::default()
+//!     .index_name("products_query_suggestions_fr")
+//!     .source_indices(vec![SourceIndexBuilder::default()
+//!         .index_name("products_fr")
+//!         .facets(vec![SourceIndexFacet {
+//!             attribute: "brand".to_string(),
+//!             amount: 3,
+//!         }])
+//!         .generate(vec![vec!["brand".to_string()]])
+//!         .build()
+//!         .unwrap()])
+//!     .languages(Languages::List(vec!["fr".to_string()]))
+//!     .exclude(vec!["test".to_string()])
+//!     .build()
+//!     .unwrap();
+//! query_suggestions.create_config(&config).await?;
+//! #   Ok(())
+//! # }
+//! ```
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    error::Error,
+    request::{auth_headers, TransportExt},
+    transport::Transport,
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// Facet whose top values are appended to the suggestions. Unlike the `facets` of a
+/// [SearchQuery](../index/struct.SearchQuery.html), which are attribute names sharing one
+/// `maxValuesPerFacet`, each facet has its own number of values.
+pub struct SourceIndexFacet {
+    /// Facet attribute
+    pub attribute: String,
+    /// Number of values to append
+    pub amount: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+/// Languages whose plurals and stop words are used to deduplicate suggestions
+pub enum Languages {
+    /// All languages when `true`, none when `false`
+    Enabled(bool),
+    /// Language codes
+    List(Vec<String>),
+}
+
+#[derive(Clone, Builder, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[builder(default)]
+#[serde(rename_all = "camelCase")]
+/// Index whose searches the suggestions are built from
+pub struct SourceIndex {
+    #[builder(setter(into))]
+    /// Index name
+    pub index_name: String,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Minimum number of hits of a suggestion
+    pub min_hits: Option<u64>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Minimum number of letters of a suggestion
+    pub min_letters: Option<u64>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Facets whose top values are appended to the suggestions
+    pub facets: Option<Vec<SourceIndexFacet>>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Facet combinations generating extra suggestions from facet values,
+    /// e.g. `[["brand"], ["brand", "category"]]`
+    pub generate: Option<Vec<Vec<String>>>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Only use searches made with these analytics tags
+    pub analytics_tags: Option<Vec<String>>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Also use the searches of the replicas of the index
+    pub replicas: Option<bool>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Indices of suggestions added as is
+    pub external: Option<Vec<String>>,
+}
+
+#[derive(Clone, Builder, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[builder(default)]
+#[serde(rename_all = "camelCase")]
+/// Query suggestions configuration
+pub struct QuerySuggestionsConfig {
+    #[builder(setter(into))]
+    /// Name of the index of suggestions
+    pub index_name: String,
+    #[builder(setter(into))]
+    /// Indices the suggestions are built from
+    pub source_indices: Vec<SourceIndex>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Languages used to deduplicate suggestions
+    pub languages: Option<Languages>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Words and patterns never suggested
+    pub exclude: Option<Vec<String>>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Keep suggestions with special characters
+    pub allow_special_characters: Option<bool>,
+    #[builder(setter(into))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Personalize the suggestions
+    pub enable_personalization: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+/// Result of a change to a configuration
+pub struct QuerySuggestionsResult {
+    /// HTTP status
+    pub status: u16,
+    /// Message
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Build status of an index of suggestions
+pub struct BuildStatus {
+    /// Name of the index of suggestions
+    pub index_name: String,
+    /// Is a build running?
+    pub is_running: bool,
+    #[serde(default)]
+    /// Date of the last build
+    pub last_built_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    /// Date of the last successful build
+    pub last_successful_built_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    /// Duration of the last successful build, e.g. `"1m12s"`
+    pub last_successful_build_duration: Option<String>,
+}
+
+enum_str!(LogLevel {
+    Info("INFO"),
+    Skip("SKIP"),
+    Error("ERROR"),
+});
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Message of the last build of an index of suggestions
+pub struct BuildLogEntry {
+    /// Date
+    pub timestamp: DateTime<Utc>,
+    /// Level
+    pub level: LogLevel,
+    /// Message
+    pub message: String,
+    /// Nesting level of the message
+    pub context_level: u64,
+}
+
+#[derive(Clone, Debug)]
+/// Query Suggestions client
+pub struct QuerySuggestions {
+    pub(crate) application_id: String,
+    pub(crate) api_key: Option<String>,
+    pub(crate) base_url: String,
+    pub(crate) transport: Arc<dyn Transport>,
+}
+
+impl QuerySuggestions {
+    /// Create a configuration and build its index of suggestions.
+    pub async fn create_config(
+        &self,
+        config: &QuerySuggestionsConfig,
+    ) -> Result<QuerySuggestionsResult, Error> {
+        let uri = format!("{}/configs", self.base_url);
+        self.transport
+            .post(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .json(config)
+            .send_json()
+            .await
+    }
+    /// Replace a configuration, identified by its `index_name`.
+    pub async fn update_config(
+        &self,
+        config: &QuerySuggestionsConfig,
+    ) -> Result<QuerySuggestionsResult, Error> {
+        let uri = format!("{}/configs/{}", self.base_url, config.index_name);
+        self.transport
+            .put(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .json(config)
+            .send_json()
+            .await
+    }
+    /// Get a configuration.
+    pub async fn get_config(&self, index_name: &str) -> Result<QuerySuggestionsConfig, Error> {
+        let uri = format!("{}/configs/{}", self.base_url, index_name);
+        self.transport
+            .get(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .send_json()
+            .await
+    }
+    /// List the configurations of the application.
+    pub async fn list_configs(&self) -> Result<Vec<QuerySuggestionsConfig>, Error> {
+        let uri = format!("{}/configs", self.base_url);
+        self.transport
+            .get(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .send_json()
+            .await
+    }
+    /// Delete a configuration. The index of suggestions is kept.
+    pub async fn delete_config(&self, index_name: &str) -> Result<QuerySuggestionsResult, Error> {
+        let uri = format!("{}/configs/{}", self.base_url, index_name);
+        self.transport
+            .delete(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .send_json()
+            .await
+    }
+    /// Get the build status of an index of suggestions.
+    pub async fn get_config_status(&self, index_name: &str) -> Result<BuildStatus, Error> {
+        let uri = format!("{}/configs/{}/status", self.base_url, index_name);
+        self.transport
+            .get(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .send_json()
+            .await
+    }
+    /// Get the messages of the last build of an index of suggestions.
+    pub async fn get_log_file(&self, index_name: &str) -> Result<Vec<BuildLogEntry>, Error> {
+        let uri = format!("{}/logs/{}", self.base_url, index_name);
+        self.transport
+            .get(&uri)
+            .headers(auth_headers(&self.application_id, self.api_key.as_deref()))
+            .send_json()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod query_suggestions_tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn test_config() {
+        let json = r#"{"indexName":"products_qs","sourceIndices":[{"indexName":"products","minHits":5,"facets":[{"attribute":"brand","amount":2}],"generate":[["brand"],["brand","color"]]}],"languages":["en","fr"],"exclude":["test"]}"#;
+        let config = serde_json::from_str::<QuerySuggestionsConfig>(json).unwrap();
+        assert_eq!(
+            config.languages,
+            Some(Languages::List(vec!["en".to_string(), "fr".to_string()]))
+        );
+        assert_eq!(serde_json::to_string(&config).unwrap(), json);
+        assert_eq!(
+            serde_json::from_str::<Languages>("true").unwrap(),
+            Languages::Enabled(true)
+        );
+    }
+
+    #[test]
+    fn test_logs() {
+        let logs = serde_json::from_str::<Vec<BuildLogEntry>>(
+            r#"[{"timestamp":"2024-01-02T03:04:05Z","level":"SKIP","message":"skipping query","contextLevel":1}]"#,
+        )
+        .unwrap();
+        assert_eq!(logs[0].level, LogLevel::Skip);
+    }
+}
@@ -0,0 +1,361 @@
+//! Client-side limits of the requests of a [Client](../client/struct.Client.html),
+//! see [Client::rate_limit](../client/struct.Client.html#method.rate_limit).
+//! ```
+//! # use algoliasearch::{rate_limit::RateLimit, Client};
+//! let client = Client::new("APP_ID", "API_KEY").rate_limit(
+//!     RateLimit::default()
+//!         .reads_per_second(50.0, 100)
+//!         .writes_per_second(5.0, 10)
+//!         .max_concurrent_writes(4)
+//!         .max_retries(5),
+//! );
+//! ```
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_lock::Semaphore;
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use reqwest::header::RETRY_AFTER;
+
+use crate::{
+    error::Error,
+    request::CallType,
+    runtime,
+    transport::{HttpRequest, HttpResponse, Transport},
+};
+
+const FIRST_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Rate {
+    per_second: f64,
+    burst: u32,
+}
+
+/// Limits of the requests of a client. Searches and other retrievals are reads,
+/// the other requests are writes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RateLimit {
+    reads: Option<Rate>,
+    writes: Option<Rate>,
+    max_concurrent_writes: Option<usize>,
+    max_retries: u32,
+}
+
+impl Default for RateLimit {
+    /// No limit, and 3 retries of the requests answered with a 429.
+    fn default() -> RateLimit {
+        RateLimit {
+            reads: None,
+            writes: None,
+            max_concurrent_writes: None,
+            max_retries: 3,
+        }
+    }
+}
+
+impl RateLimit {
+    /// Send `per_second` reads per second on average, and at most `burst` at once.
+    /// A rate that isn't a positive number removes the limit.
+    pub fn reads_per_second(mut self, per_second: f64, burst: u32) -> RateLimit {
+        self.reads = Rate::new(per_second, burst);
+        self
+    }
+    /// Send `per_second` writes per second on average, and at most `burst` at once.
+    /// A rate that isn't a positive number removes the limit.
+    pub fn writes_per_second(mut self, per_second: f64, burst: u32) -> RateLimit {
+        self.writes = Rate::new(per_second, burst);
+        self
+    }
+    /// Maximum number of writes in flight, the others wait for one to finish.
+    pub fn max_concurrent_writes(mut self, max_concurrent_writes: usize) -> RateLimit {
+        self.max_concurrent_writes = Some(max_concurrent_writes.max(1));
+        self
+    }
+    /// Number of retries of a request answered with a 429, after the delay of its
+    /// `Retry-After` header, or an exponential backoff without one, 30 seconds at most.
+    pub fn max_retries(mut self, max_retries: u32) -> RateLimit {
+        self.max_retries = max_retries;
+        self
+    }
+}
+
+impl Rate {
+    // No rate, so no limit, unless `per_second` is positive and finite.
+    fn new(per_second: f64, burst: u32) -> Option<Rate> {
+        if !(per_second > 0.0 && per_second.is_finite()) {
+            return None;
+        }
+        Some(Rate {
+            per_second,
+            burst: burst.max(1),
+        })
+    }
+}
+
+// Token bucket, holding up to `burst` tokens.
+#[derive(Debug)]
+struct Bucket {
+    rate: Rate,
+    // tokens, and when they were last refilled
+    state: Mutex<(f64, Instant)>,
+}
+
+impl Bucket {
+    fn new(rate: Rate) -> Bucket {
+        Bucket {
+            rate,
+            state: Mutex::new((f64::from(rate.burst), Instant::now())),
+        }
+    }
+    // Take a token, waiting for one if the bucket is empty.
+    async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                let (tokens, refilled_at) = &mut *state;
+                let now = Instant::now();
+                let refill = now.duration_since(*refilled_at).as_secs_f64() * self.rate.per_second;
+                *tokens = (*tokens + refill).min(f64::from(self.rate.burst));
+                *refilled_at = now;
+                if *tokens >= 1.0 {
+                    *tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - *tokens) / self.rate.per_second)
+            };
+            runtime::sleep(wait).await;
+        }
+    }
+}
+
+// State of the limits, shared by the clones of a client.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    reads: Option<Bucket>,
+    writes: Option<Bucket>,
+    writes_in_flight: Option<Semaphore>,
+    max_retries: u32,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: &RateLimit) -> RateLimiter {
+        RateLimiter {
+            reads: limit.reads.map(Bucket::new),
+            writes: limit.writes.map(Bucket::new),
+            writes_in_flight: limit.max_concurrent_writes.map(Semaphore::new),
+            max_retries: limit.max_retries,
+        }
+    }
+}
+
+// Transport applying the limits around another one.
+#[derive(Debug)]
+pub(crate) struct RateLimitedTransport {
+    pub(crate) transport: Arc<dyn Transport>,
+    pub(crate) limiter: Arc<RateLimiter>,
+}
+
+impl Transport for RateLimitedTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+        Box::pin(async move {
+            let limiter = &self.limiter;
+            let (bucket, semaphore) = match CallType::of(&request) {
+                CallType::Read => (&limiter.reads, None),
+                CallType::Write => (&limiter.writes, limiter.writes_in_flight.as_ref()),
+            };
+            let _permit = match semaphore {
+                Some(semaphore) => Some(semaphore.acquire().await),
+                None => None,
+            };
+            let mut retries = 0;
+            loop {
+                if let Some(bucket) = bucket {
+                    bucket.acquire().await;
+                }
+                let response = self.transport.send(request.clone()).await?;
+                if response.status != 429 || retries >= limiter.max_retries {
+                    return Ok(response);
+                }
+                runtime::sleep(retry_after(&response).unwrap_or_else(|| backoff(retries))).await;
+                retries += 1;
+                #[cfg(feature = "tracing")]
+                tracing::Span::current().record("retries", u64::from(retries));
+            }
+        })
+    }
+}
+
+// Delay asked by the `Retry-After` header, in seconds or as a date, up to `MAX_BACKOFF`.
+fn retry_after(response: &HttpResponse) -> Option<Duration> {
+    let value = response.headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    let delay = match value.parse() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => {
+            let date = DateTime::parse_from_rfc2822(value).ok()?;
+            (date.with_timezone(&Utc) - Utc::now())
+                .to_std()
+                .unwrap_or_default()
+        }
+    };
+    Some(delay.min(MAX_BACKOFF))
+}
+
+fn backoff(retries: u32) -> Duration {
+    FIRST_BACKOFF
+        .saturating_mul(1 << retries.min(16))
+        .min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod rate_limit_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::transport::{HeaderMap, Method};
+
+    fn request(method: Method, path: &str) -> HttpRequest {
+        HttpRequest {
+            method,
+            url: format!("https://APP-dsn.algolia.net{}", path),
+            headers: HeaderMap::new(),
+            body: None,
+            timeout: None,
+        }
+    }
+
+    // Answers with the statuses in order, then with 200.
+    #[derive(Debug, Default)]
+    struct Statuses {
+        statuses: Mutex<Vec<u16>>,
+        calls: AtomicUsize,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    impl Transport for Statuses {
+        fn send(&self, _: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+            Box::pin(async move {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+                runtime::sleep(Duration::from_millis(10)).await;
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+                let mut statuses = self.statuses.lock().unwrap();
+                let status = if statuses.is_empty() {
+                    200
+                } else {
+                    statuses.remove(0)
+                };
+                let mut headers = HeaderMap::new();
+                headers.insert(RETRY_AFTER, "0".parse().unwrap());
+                Ok(HttpResponse {
+                    status,
+                    headers,
+                    body: vec![],
+                })
+            })
+        }
+    }
+
+    fn limited(statuses: Vec<u16>, limit: RateLimit) -> (Arc<Statuses>, RateLimitedTransport) {
+        let inner = Arc::new(Statuses {
+            statuses: Mutex::new(statuses),
+            ..Default::default()
+        });
+        let transport = RateLimitedTransport {
+            transport: inner.clone(),
+            limiter: Arc::new(RateLimiter::new(&limit)),
+        };
+        (inner, transport)
+    }
+
+    #[test]
+    fn test_call_type() {
+        let search = request(Method::POST, "/1/indexes/products/query");
+        assert_eq!(CallType::of(&search), CallType::Read);
+        let get = request(Method::GET, "/1/indexes/products/42");
+        assert_eq!(CallType::of(&get), CallType::Read);
+        let batch = request(Method::POST, "/1/indexes/products/batch");
+        assert_eq!(CallType::of(&batch), CallType::Write);
+    }
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(0), Duration::from_millis(500));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(30), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_invalid_rates() {
+        for per_second in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let limit = RateLimit::default()
+                .reads_per_second(per_second, 10)
+                .writes_per_second(per_second, 10);
+            assert_eq!(limit, RateLimit::default());
+        }
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let response = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(RETRY_AFTER, value.parse().unwrap());
+            HttpResponse {
+                status: 429,
+                headers,
+                body: vec![],
+            }
+        };
+        assert_eq!(retry_after(&response("2")), Some(Duration::from_secs(2)));
+        assert_eq!(retry_after(&response("86400")), Some(MAX_BACKOFF));
+        assert_eq!(
+            retry_after(&response("Wed, 21 Oct 2099 07:28:00 GMT")),
+            Some(MAX_BACKOFF)
+        );
+        assert_eq!(retry_after(&response("soon")), None);
+    }
+
+    #[tokio::test]
+    async fn test_retry() {
+        let (inner, transport) = limited(vec![429, 429], RateLimit::default());
+        let response = transport
+            .send(request(Method::POST, "/1/indexes/products/batch"))
+            .await
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 3);
+
+        let (inner, transport) = limited(vec![429, 429], RateLimit::default().max_retries(1));
+        let response = transport
+            .send(request(Method::POST, "/1/indexes/products/batch"))
+            .await
+            .unwrap();
+        assert_eq!(response.status, 429);
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_limits() {
+        let (inner, transport) = limited(
+            vec![],
+            RateLimit::default()
+                .writes_per_second(100.0, 2)
+                .max_concurrent_writes(2),
+        );
+        let start = Instant::now();
+        let writes =
+            (0..6).map(|_| transport.send(request(Method::POST, "/1/indexes/products/batch")));
+        for response in futures::future::join_all(writes).await {
+            assert_eq!(response.unwrap().status, 200);
+        }
+        assert_eq!(inner.max_in_flight.load(Ordering::SeqCst), 2);
+        // 2 tokens at first, then one every 10ms
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+}
@@ -0,0 +1,199 @@
+//! [Recommend API](https://www.algolia.com/doc/rest-api/recommend/) models,
+//! see [Client::get_recommendations](../client/struct.Client.html#method.get_recommendations).
+use serde::de::DeserializeOwned;
+
+use crate::index::{params, SearchQuery};
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "model", rename_all = "kebab-case")]
+/// Recommendation model, with the item or facet it applies to.
+pub enum RecommendModel {
+    /// Items related to an item
+    RelatedProducts {
+        #[serde(rename = "objectID")]
+        /// Object ID of the item
+        object_id: String,
+    },
+    /// Items frequently bought together with an item
+    BoughtTogether {
+        #[serde(rename = "objectID")]
+        /// Object ID of the item
+        object_id: String,
+    },
+    /// Items looking similar to an item
+    LookingSimilar {
+        #[serde(rename = "objectID")]
+        /// Object ID of the item
+        object_id: String,
+    },
+    /// Trending items, overall or for a facet value
+    #[serde(rename_all = "camelCase")]
+    TrendingItems {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        /// Facet attribute
+        facet_name: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        /// Facet value
+        facet_value: Option<String>,
+    },
+    /// Trending values of a facet
+    #[serde(rename_all = "camelCase")]
+    TrendingFacets {
+        /// Facet attribute
+        facet_name: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Builder)]
+#[serde(rename_all = "camelCase")]
+/// A recommendation request.
+/// ```
+/// # use algoliasearch::recommend::{RecommendModel, RecommendRequestBuilder};
+/// let request = RecommendRequestBuilder::default()
+///     .index_name("products")
+///     .model(RecommendModel::BoughtTogether {
+///         object_id: "42".to_string(),
+///     })
+///     .max_recommendations(5)
+///     .build()
+///     .unwrap();
+/// ```
+pub struct RecommendRequest {
+    #[builder(setter(into))]
+    /// Index name
+    index_name: String,
+    #[serde(flatten)]
+    /// Model
+    model: RecommendModel,
+    #[builder(default, setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Minimum score of the recommendations, from 0 to 100
+    threshold: Option<u32>,
+    #[builder(default, setter(into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Maximum number of recommendations
+    max_recommendations: Option<u64>,
+    #[builder(default, setter(into))]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "params::serialize_object"
+    )]
+    /// Search parameters filtering the recommendations
+    query_parameters: Option<SearchQuery>,
+    #[builder(default, setter(into))]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "params::serialize_object"
+    )]
+    /// Search parameters used when there are not enough recommendations
+    fallback_parameters: Option<SearchQuery>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct RecommendRequests<'a> {
+    pub(crate) requests: &'a [RecommendRequest],
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+/// A recommended item or facet value
+pub struct RecommendHit<T> {
+    #[serde(flatten)]
+    /// Record, or [TrendingFacetHit](struct.TrendingFacetHit.html) for trending facets
+    pub hit: T,
+    #[serde(rename = "_score")]
+    /// Confidence score, from 0 to 100
+    pub score: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// A trending facet value
+pub struct TrendingFacetHit {
+    /// Facet attribute
+    pub facet_name: String,
+    /// Facet value
+    pub facet_value: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", bound = "T: DeserializeOwned")]
+/// Recommendations of a request
+pub struct RecommendResult<T> {
+    /// Hits
+    pub hits: Vec<RecommendHit<T>>,
+    #[serde(default, rename = "processingTimeMS")]
+    /// Processing time (ms)
+    pub processing_time_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+pub(crate) struct RecommendResults<T> {
+    pub(crate) results: Vec<RecommendResult<T>>,
+}
+
+#[cfg(test)]
+mod recommend_tests {
+    use super::*;
+    use crate::SearchQueryBuilder;
+    use serde_json;
+
+    #[test]
+    fn test_serialize() {
+        let requests = vec![
+            RecommendRequestBuilder::default()
+                .index_name("products")
+                .model(RecommendModel::RelatedProducts {
+                    object_id: "42".to_string(),
+                })
+                .threshold(40)
+                .query_parameters(
+                    SearchQueryBuilder::default()
+                        .filters("brand:Apple".to_string())
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+            RecommendRequestBuilder::default()
+                .index_name("products")
+                .model(RecommendModel::TrendingFacets {
+                    facet_name: "brand".to_string(),
+                })
+                .build()
+                .unwrap(),
+        ];
+        assert_eq!(
+            serde_json::to_string(&RecommendRequests {
+                requests: &requests
+            })
+            .unwrap(),
+            r#"{"requests":[{"indexName":"products","model":"related-products","objectID":"42","threshold":40,"queryParameters":{"filters":"brand:Apple"}},{"indexName":"products","model":"trending-facets","facetName":"brand"}]}"#
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Product {
+            name: String,
+        }
+        let res = serde_json::from_str::<RecommendResults<Product>>(
+            r#"{"results":[{"hits":[{"objectID":"1","name":"Case","_score":87.5}],"processingTimeMS":2}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            res.results[0].hits[0].hit,
+            Product {
+                name: "Case".to_string()
+            }
+        );
+        assert_eq!(res.results[0].hits[0].score, Some(87.5));
+        let res = serde_json::from_str::<RecommendResults<TrendingFacetHit>>(
+            r#"{"results":[{"hits":[{"facetName":"brand","facetValue":"Apple","_score":12}]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(res.results[0].hits[0].hit.facet_value, "Apple");
+    }
+}
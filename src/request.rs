@@ -0,0 +1,376 @@
+use std::{sync::Arc, time::Duration};
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    error::Error,
+    instrumentation::Operation,
+    transport::{HttpRequest, Method, Transport},
+};
+
+/// Options of a single request, see the `*_with_options` methods of
+/// [Index](index/struct.Index.html).
+/// ```
+/// # use std::time::Duration;
+/// # use algoliasearch::RequestOptions;
+/// let options = RequestOptions::default()
+///     .header("X-Forwarded-For", "203.0.113.7")
+///     .header("X-Algolia-UserToken", "user-42")
+///     .read_timeout(Duration::from_secs(2));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RequestOptions {
+    headers: Vec<(String, String)>,
+    query_parameters: Vec<(String, String)>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    forward_to_replicas: Option<bool>,
+}
+
+impl RequestOptions {
+    /// Add a header.
+    pub fn header(mut self, name: &str, value: &str) -> RequestOptions {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+    /// Add a query string parameter.
+    pub fn query_parameter(mut self, name: &str, value: &str) -> RequestOptions {
+        self.query_parameters
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+    /// Timeout of searches and other reads.
+    pub fn read_timeout(mut self, timeout: Duration) -> RequestOptions {
+        self.read_timeout = Some(timeout);
+        self
+    }
+    /// Timeout of writes.
+    pub fn write_timeout(mut self, timeout: Duration) -> RequestOptions {
+        self.write_timeout = Some(timeout);
+        self
+    }
+    /// Also apply settings changes to the replicas of the index,
+    /// ignored by the other operations.
+    pub fn forward_to_replicas(mut self, forward_to_replicas: bool) -> RequestOptions {
+        self.forward_to_replicas = Some(forward_to_replicas);
+        self
+    }
+}
+
+// Characters escaped in a path segment, all but the unreserved ones.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+// Percent-encode a value interpolated in the path of a request.
+pub(crate) fn encode_path_segment(segment: &str) -> String {
+    utf8_percent_encode(segment, PATH_SEGMENT).to_string()
+}
+
+// Authentication headers of the requests of an application, without the API key
+// when it is set by a credentials provider.
+pub(crate) fn auth_headers(application_id: &str, api_key: Option<&str>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        crate::APPLICATION_ID_HEADER,
+        application_id.parse().unwrap(),
+    );
+    if let Some(api_key) = api_key {
+        headers.insert(crate::API_KEY_HEADER, api_key.parse().unwrap());
+    }
+    headers
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum CallType {
+    Read,
+    Write,
+}
+
+impl CallType {
+    // Searches and other retrievals are reads, even when they are POSTed.
+    pub(crate) fn of(request: &HttpRequest) -> CallType {
+        let path = request.path().split('?').next().unwrap_or_default();
+        let is_read = request.method == Method::GET
+            || [
+                "/query",
+                "/queries",
+                "/objects",
+                "/browse",
+                "/search",
+                "/recommendations",
+            ]
+            .iter()
+            .any(|suffix| path.ends_with(suffix));
+        if is_read {
+            CallType::Read
+        } else {
+            CallType::Write
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiErrorBody {
+    message: String,
+}
+
+/// Request being built, sent through a [Transport](../transport/trait.Transport.html).
+pub(crate) struct RequestBuilder<'a> {
+    transport: &'a dyn Transport,
+    method: Method,
+    url: String,
+    query: String,
+    headers: HeaderMap,
+    body: Option<Vec<u8>>,
+    timeout: Option<Duration>,
+    operation: Option<Operation>,
+    error: Option<Error>,
+}
+
+impl<'a> RequestBuilder<'a> {
+    fn new(transport: &'a dyn Transport, method: Method, url: &str) -> RequestBuilder<'a> {
+        RequestBuilder {
+            transport,
+            method,
+            url: url.to_string(),
+            query: String::new(),
+            headers: HeaderMap::new(),
+            body: None,
+            timeout: None,
+            operation: None,
+            error: None,
+        }
+    }
+
+    pub(crate) fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    pub(crate) fn header(mut self, name: &str, value: &str) -> Self {
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                self.headers.insert(name, value);
+            }
+            _ => self.error = Some(Error::InvalidHeader(name.to_string())),
+        }
+        self
+    }
+
+    pub(crate) fn query<T: Serialize + ?Sized>(mut self, query: &T) -> Self {
+        let query = serde_urlencoded::to_string(query).expect("failed to encode query string");
+        if !query.is_empty() {
+            if !self.query.is_empty() {
+                self.query.push('&');
+            }
+            self.query.push_str(&query);
+        }
+        self
+    }
+
+    pub(crate) fn json<T: Serialize + ?Sized>(mut self, body: &T) -> Self {
+        match serde_json::to_vec(body) {
+            Ok(body) => {
+                self.headers
+                    .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+                self.body = Some(body);
+            }
+            Err(e) => self.error = Some(e.into()),
+        }
+        self
+    }
+
+    /// Apply the options of a request.
+    pub(crate) fn with_options(mut self, options: &RequestOptions, call_type: CallType) -> Self {
+        for (name, value) in &options.headers {
+            self = self.header(name, value);
+        }
+        self = self.query(&options.query_parameters);
+        self.timeout = match call_type {
+            CallType::Read => options.read_timeout,
+            CallType::Write => options.write_timeout,
+        };
+        self
+    }
+
+    /// Apply the `forward_to_replicas` option, on the operations accepting it.
+    pub(crate) fn forward_to_replicas(self, options: &RequestOptions) -> Self {
+        match options.forward_to_replicas {
+            Some(forward_to_replicas) => self.query(&[("forwardToReplicas", forward_to_replicas)]),
+            None => self,
+        }
+    }
+
+    /// Record the request as an operation on an index.
+    pub(crate) fn operation(mut self, name: &'static str, index_name: &str) -> Self {
+        self.operation = Some(Operation::new(name, index_name));
+        self
+    }
+
+    pub(crate) fn build(self) -> Result<HttpRequest, Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let url = if self.query.is_empty() {
+            self.url
+        } else {
+            format!("{}?{}", self.url, self.query)
+        };
+        Ok(HttpRequest {
+            method: self.method,
+            url,
+            headers: self.headers,
+            body: self.body,
+            timeout: self.timeout,
+        })
+    }
+
+    /// Send the request and get the body of the response,
+    /// turning non-2xx answers into [Error::Api](../error/enum.Error.html#variant.Api).
+    pub(crate) async fn send(mut self) -> Result<Vec<u8>, Error> {
+        let transport = self.transport;
+        let operation = self.operation.take();
+        let request = self.build()?;
+        let response = match operation {
+            Some(operation) => operation.run(transport, request).await?,
+            None => transport.send(request).await?,
+        };
+        if (200..300).contains(&response.status) {
+            return Ok(response.body);
+        }
+        let message = serde_json::from_slice::<ApiErrorBody>(&response.body)
+            .map(|body| body.message)
+            .unwrap_or_else(|_| String::from_utf8_lossy(&response.body).into_owned());
+        Err(Error::Api {
+            status: response.status,
+            message,
+        })
+    }
+
+    /// Send the request and decode the JSON response.
+    pub(crate) async fn send_json<R: DeserializeOwned>(self) -> Result<R, Error> {
+        let body = self.send().await?;
+        serde_json::from_slice(&body).map_err(|e| e.into())
+    }
+}
+
+/// Start requests from a transport.
+pub(crate) trait TransportExt {
+    fn get(&self, url: &str) -> RequestBuilder<'_>;
+    fn post(&self, url: &str) -> RequestBuilder<'_>;
+    fn put(&self, url: &str) -> RequestBuilder<'_>;
+    fn delete(&self, url: &str) -> RequestBuilder<'_>;
+}
+
+impl TransportExt for Arc<dyn Transport> {
+    fn get(&self, url: &str) -> RequestBuilder<'_> {
+        RequestBuilder::new(self.as_ref(), Method::GET, url)
+    }
+    fn post(&self, url: &str) -> RequestBuilder<'_> {
+        RequestBuilder::new(self.as_ref(), Method::POST, url)
+    }
+    fn put(&self, url: &str) -> RequestBuilder<'_> {
+        RequestBuilder::new(self.as_ref(), Method::PUT, url)
+    }
+    fn delete(&self, url: &str) -> RequestBuilder<'_> {
+        RequestBuilder::new(self.as_ref(), Method::DELETE, url)
+    }
+}
+
+#[cfg(test)]
+mod request_tests {
+    use super::*;
+    use futures::future::BoxFuture;
+
+    use crate::transport::HttpResponse;
+
+    #[derive(Debug)]
+    struct Fake(u16, &'static str);
+
+    impl Transport for Fake {
+        fn send(&self, _: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+            let response = HttpResponse {
+                status: self.0,
+                headers: HeaderMap::new(),
+                body: self.1.as_bytes().to_vec(),
+            };
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    #[test]
+    fn test_with_options() {
+        let transport: Arc<dyn Transport> = Arc::new(Fake(200, "{}"));
+        let options = RequestOptions::default()
+            .header("X-Forwarded-For", "203.0.113.7")
+            .query_parameter("getVersion", "2")
+            .forward_to_replicas(true)
+            .write_timeout(Duration::from_secs(30));
+        let request = transport
+            .put("https://example.org/1/indexes/products/settings")
+            .with_options(&options, CallType::Write)
+            .forward_to_replicas(&options)
+            .build()
+            .unwrap();
+        assert_eq!(request.headers["x-forwarded-for"], "203.0.113.7");
+        assert_eq!(
+            request.url,
+            "https://example.org/1/indexes/products/settings?getVersion=2&forwardToReplicas=true"
+        );
+        assert_eq!(request.timeout, Some(Duration::from_secs(30)));
+        let request = transport
+            .get("https://example.org/1/indexes/products/settings")
+            .with_options(&options, CallType::Read)
+            .build()
+            .unwrap();
+        assert_eq!(request.timeout, None);
+        assert_eq!(
+            request.url,
+            "https://example.org/1/indexes/products/settings?getVersion=2"
+        );
+        assert!(matches!(
+            transport
+                .get("https://example.org")
+                .header("bad header", "value")
+                .build(),
+            Err(Error::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn test_encode_path_segment() {
+        assert_eq!(encode_path_segment("user-42_a.b~c"), "user-42_a.b~c");
+        assert_eq!(encode_path_segment("a/b?c#d e"), "a%2Fb%3Fc%23d%20e");
+    }
+
+    #[tokio::test]
+    async fn test_send_json() {
+        let transport: Arc<dyn Transport> = Arc::new(Fake(200, r#"{"message":"ok"}"#));
+        let body: serde_json::Value = transport
+            .get("https://example.org")
+            .send_json()
+            .await
+            .unwrap();
+        assert_eq!(body["message"], "ok");
+        let transport: Arc<dyn Transport> = Arc::new(Fake(403, r#"{"message":"Invalid API key"}"#));
+        match transport
+            .get("https://example.org")
+            .send_json::<serde_json::Value>()
+            .await
+        {
+            Err(Error::Api { status, message }) => {
+                assert_eq!(status, 403);
+                assert_eq!(message, "Invalid API key");
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
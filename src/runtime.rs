@@ -0,0 +1,71 @@
+//! Timers of the async runtime, selected with the `runtime-tokio` (default),
+//! `runtime-async-std` or `runtime-smol` feature. When several are enabled,
+//! async-std is preferred to smol, and smol to tokio.
+use std::{future::Future, time::Duration};
+
+use futures::future::{self, Either};
+
+#[cfg(not(any(
+    feature = "runtime-tokio",
+    feature = "runtime-async-std",
+    feature = "runtime-smol"
+)))]
+compile_error!("one of the runtime-tokio, runtime-async-std or runtime-smol features is required");
+
+/// Wait for a duration.
+#[cfg(feature = "runtime-async-std")]
+pub(crate) async fn sleep(duration: Duration) {
+    async_std::task::sleep(duration).await
+}
+
+/// Wait for a duration.
+#[cfg(all(feature = "runtime-smol", not(feature = "runtime-async-std")))]
+pub(crate) async fn sleep(duration: Duration) {
+    async_io::Timer::after(duration).await;
+}
+
+/// Wait for a duration.
+#[cfg(all(
+    feature = "runtime-tokio",
+    not(any(feature = "runtime-async-std", feature = "runtime-smol"))
+))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+/// Output of a future, or `None` if it is not ready within the duration.
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    match future::select(Box::pin(future), Box::pin(sleep(duration))).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
+
+/// Run a future of the tokio ecosystem, such as a reqwest request, on the runtime.
+#[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+pub(crate) fn compat<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    async_compat::Compat::new(future)
+}
+
+/// Run a future of the tokio ecosystem, such as a reqwest request, on the runtime.
+#[cfg(not(any(feature = "runtime-async-std", feature = "runtime-smol")))]
+pub(crate) fn compat<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    future
+}
+
+#[cfg(test)]
+mod runtime_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timeout() {
+        assert_eq!(
+            timeout(Duration::from_secs(5), async { 42 }).await,
+            Some(42)
+        );
+        assert_eq!(
+            timeout(Duration::from_millis(10), future::pending::<()>()).await,
+            None
+        );
+    }
+}
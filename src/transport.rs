@@ -0,0 +1,122 @@
+//! HTTP layer of the clients. [ReqwestTransport](struct.ReqwestTransport.html) is used by default,
+//! another [Transport](trait.Transport.html) can be set with
+//! [Client::transport](../client/struct.Client.html#method.transport), for instance to go
+//! through a custom proxy or to answer requests from memory in tests.
+//! ```
+//! # use algoliasearch::{transport::{HttpRequest, HttpResponse, Transport}, Client, Error};
+//! # use futures::future::BoxFuture;
+//! #[derive(Debug)]
+//! struct NotFound;
+//!
+//! impl Transport for NotFound {
+//!     fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+//!         Box::pin(async move {
+//!             Ok(HttpResponse {
+//!                 status: 404,
+//!                 headers: Default::default(),
+//!                 body: br#"{"message":"not found"}"#.to_vec(),
+//!             })
+//!         })
+//!     }
+//! }
+//!
+//! let client = Client::new("APP_ID", "API_KEY").transport(NotFound);
+//! ```
+use std::{fmt, time::Duration};
+
+use futures::future::BoxFuture;
+pub use reqwest::{header::HeaderMap, Method};
+
+use crate::{error::Error, runtime};
+
+#[derive(Clone, Debug)]
+/// A request to send
+pub struct HttpRequest {
+    /// Method
+    pub method: Method,
+    /// URL, query string included
+    pub url: String,
+    /// Headers, authentication included
+    pub headers: HeaderMap,
+    /// Body
+    pub body: Option<Vec<u8>>,
+    /// Timeout of the whole request
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    /// Path of the URL, query string included, e.g. `/1/indexes/products/query`.
+    pub fn path(&self) -> &str {
+        let after_scheme = self
+            .url
+            .find("://")
+            .map(|position| position + 3)
+            .unwrap_or(0);
+        self.url[after_scheme..]
+            .find('/')
+            .map(|position| &self.url[after_scheme + position..])
+            .unwrap_or("/")
+    }
+    /// Headers with the API key hidden, to be logged.
+    pub fn redacted_headers(&self) -> HeaderMap {
+        let mut headers = self.headers.clone();
+        if headers.contains_key(crate::API_KEY_HEADER) {
+            headers.insert(crate::API_KEY_HEADER, "<redacted>".parse().unwrap());
+        }
+        headers
+    }
+}
+
+#[derive(Clone, Debug)]
+/// A response, whatever its status
+pub struct HttpResponse {
+    /// Status code
+    pub status: u16,
+    /// Headers
+    pub headers: HeaderMap,
+    /// Body
+    pub body: Vec<u8>,
+}
+
+/// Sends requests. Only failures to get a response are errors, responses with
+/// an error status are handled by the clients.
+pub trait Transport: fmt::Debug + Send + Sync {
+    /// Send a request.
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>>;
+}
+
+#[derive(Clone, Debug, Default)]
+/// Transport using a [reqwest](https://docs.rs/reqwest) client
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    /// Transport using a configured client, e.g. with a proxy.
+    pub fn new(client: reqwest::Client) -> ReqwestTransport {
+        ReqwestTransport { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+        let mut builder = self
+            .client
+            .request(request.method, &request.url)
+            .headers(request.headers);
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+        if let Some(timeout) = request.timeout {
+            builder = builder.timeout(timeout);
+        }
+        Box::pin(runtime::compat(async move {
+            let response = builder.send().await?;
+            Ok(HttpResponse {
+                status: response.status().as_u16(),
+                headers: response.headers().clone(),
+                body: response.bytes().await?.to_vec(),
+            })
+        }))
+    }
+}